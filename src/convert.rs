@@ -0,0 +1,123 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use alloc::{string::String, vec::Vec};
+
+use crate::*;
+
+/// Conversion of a Rust value into a TTLV item. Can be derived for structs with `#[derive(ToTtlv)]`.
+pub trait ToTtlv {
+    fn to_ttlv(&self, tag: u32) -> Ttlv<'_>;
+}
+
+/// Conversion of a struct field into children of the structure. Every `ToTtlv` value appends one child, missing
+/// optional values append nothing and sequences append one child per element.
+pub trait ToChildren {
+    fn to_children<'a>(&'a self, tag: u32, children: &mut Vec<Ttlv<'a>>);
+}
+
+impl<T: ToTtlv> ToChildren for T {
+    fn to_children<'a>(&'a self, tag: u32, children: &mut Vec<Ttlv<'a>>) {
+        children.push(self.to_ttlv(tag));
+    }
+}
+
+/// Conversion of a TTLV item into a Rust value. Can be derived for structs with `#[derive(FromTtlv)]`.
+pub trait FromTtlv<'a>: Sized {
+    fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error>;
+
+    /// Collects this value from the children of a structure. Optional values may be missing and sequences collect every
+    /// child with the tag.
+    fn from_children(parent: &'a Ttlv<'a>, tag: u32) -> Result<Self, Error> {
+        Self::from_ttlv(parent.path(&[tag])?)
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($type_:ty => |$val:ident| $value:expr),* $(,)?) => {
+        $(
+            impl<'a> ToTtlv for $type_ {
+                fn to_ttlv(&self, tag: u32) -> Ttlv<'_> {
+                    let $val = self;
+                    Ttlv::new(tag, $value)
+                }
+            }
+            impl<'a> FromTtlv<'a> for $type_ {
+                fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+                    ttlv.value()
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    i32 => |val| Value::Integer(*val),
+    i64 => |val| Value::LongInteger(*val),
+    u32 => |val| Value::Enumeration(*val),
+    bool => |val| Value::Boolean(*val),
+    &'a str => |val| Value::TextString(Text::Borrowed(val)),
+    &'a [u8] => |val| Value::ByteString(Bytes::Borrowed(val)),
+    TwosComplement<'a> => |val| Value::BigInteger(Bytes::Borrowed(val.0)),
+    DateTimeMicros => |val| Value::DateTimeExtended(val.0),
+}
+
+impl ToTtlv for String {
+    fn to_ttlv(&self, tag: u32) -> Ttlv<'_> {
+        Ttlv::new(tag, Value::TextString(Text::Borrowed(self)))
+    }
+}
+impl<'a> FromTtlv<'a> for String {
+    fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+        ttlv.value::<&str>().map(String::from)
+    }
+}
+
+impl ToTtlv for Vec<u8> {
+    fn to_ttlv(&self, tag: u32) -> Ttlv<'_> {
+        Ttlv::new(tag, Value::ByteString(Bytes::Borrowed(self)))
+    }
+}
+impl<'a> FromTtlv<'a> for Vec<u8> {
+    fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+        ttlv.value::<&[u8]>().map(Vec::from)
+    }
+}
+
+impl<T: ToChildren> ToChildren for Option<T> {
+    fn to_children<'a>(&'a self, tag: u32, children: &mut Vec<Ttlv<'a>>) {
+        if let Some(val) = self {
+            val.to_children(tag, children);
+        }
+    }
+}
+impl<'a, T: FromTtlv<'a>> FromTtlv<'a> for Option<T> {
+    fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+        T::from_ttlv(ttlv).map(Some)
+    }
+    fn from_children(parent: &'a Ttlv<'a>, tag: u32) -> Result<Self, Error> {
+        match parent.child_iter()?.find(|c| c.tag::<u32>() == tag) {
+            Some(child) => T::from_ttlv(child).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: ToChildren> ToChildren for Vec<T> {
+    fn to_children<'a>(&'a self, tag: u32, children: &mut Vec<Ttlv<'a>>) {
+        for val in self {
+            val.to_children(tag, children);
+        }
+    }
+}
+impl<'a, T: FromTtlv<'a>> FromTtlv<'a> for Vec<T> {
+    fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+        ttlv.child_iter()?.map(T::from_ttlv).collect()
+    }
+    fn from_children(parent: &'a Ttlv<'a>, tag: u32) -> Result<Self, Error> {
+        parent
+            .child_iter()?
+            .filter(|c| c.tag::<u32>() == tag)
+            .map(T::from_ttlv)
+            .collect()
+    }
+}
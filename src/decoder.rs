@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use alloc::vec::Vec;
+
+use crate::{util::message_len, Error, Ttlv, DEFAULT_MAX_MESSAGE_LEN};
+
+/// Push-style decoder for top-level TTLV messages arriving in arbitrary chunks, e.g. from a non-blocking socket. Each
+/// header is parsed once, as soon as its 8 bytes have arrived, and each message is decoded once, when complete.
+pub struct TtlvDecoder {
+    buf: Vec<u8>,
+    len: Option<usize>, // Encoded length of the current message, once its header has arrived
+    complete: bool,
+    max_len: usize,
+}
+
+impl Default for TtlvDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TtlvDecoder {
+    pub fn new() -> Self {
+        TtlvDecoder {
+            buf: Vec::new(),
+            len: None,
+            complete: false,
+            max_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+    /// Rejects messages whose encoded length, including the header, exceeds `max_len` as soon as their header arrives.
+    /// Defaults to `DEFAULT_MAX_MESSAGE_LEN`.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Number of bytes still missing from the current message, or from the next one once the current message has been
+    /// returned. Until a message's header has arrived, this only counts the missing header bytes.
+    pub fn needed(&self) -> usize {
+        if self.complete {
+            8
+        } else {
+            self.len.unwrap_or(8) - self.buf.len()
+        }
+    }
+
+    /// Takes as many bytes from the front of `input` as the current message needs, advancing `input` past them.
+    /// Returns the strictly decoded message once it is complete, and starts on the next message at the following call.
+    /// Leftover bytes in `input` belong to the next message.
+    ///
+    /// Errors discard the current message, since the stream can no longer be framed reliably.
+    pub fn feed(&mut self, input: &mut &[u8]) -> Result<Option<Ttlv<'_>>, Error> {
+        if self.complete {
+            self.reset();
+        }
+        loop {
+            let take = self.needed().min(input.len());
+            self.buf.extend_from_slice(&input[..take]);
+            *input = &input[take..];
+            if self.needed() > 0 {
+                return Ok(None);
+            }
+            if self.len.is_some() {
+                break;
+            }
+            match message_len(&self.buf, self.max_len) {
+                Ok(len) => self.len = Some(len),
+                Err(e) => {
+                    self.reset();
+                    return Err(e);
+                }
+            }
+        }
+
+        self.complete = true;
+        let (ttlv, _) = Ttlv::decode_strict(&self.buf)?;
+        Ok(Some(ttlv))
+    }
+
+    /// Discards any partially received message.
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.len = None;
+        self.complete = false;
+    }
+}
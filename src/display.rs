@@ -0,0 +1,277 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use core::{fmt, mem};
+
+use scroll::{Cread, BE};
+
+use crate::{
+    ttlv::{decode_header, decode_primitive},
+    util::{padded_len, Hex},
+    *,
+};
+
+/// Resolves a tag to a human-readable name, e.g. `kmip::tag_name`.
+pub type TagNames = fn(u32) -> Option<&'static str>;
+
+/// Displays a TTLV tree as an indented list of items with their tag, type and value.
+pub struct Pretty<'t, 'a, F> {
+    ttlv: &'t Ttlv<'a>,
+    names: F,
+}
+
+impl<'a> Ttlv<'a> {
+    /// Displays the tree with hex tags.
+    pub fn pretty(&self) -> Pretty<'_, 'a, TagNames> {
+        self.pretty_with(|_| None)
+    }
+    /// Displays the tree, naming tags with `names` wherever it resolves them.
+    pub fn pretty_with<F: Fn(u32) -> Option<&'static str>>(&self, names: F) -> Pretty<'_, 'a, F> {
+        Pretty { ttlv: self, names }
+    }
+}
+
+impl<'t, 'a, F: Fn(u32) -> Option<&'static str>> Pretty<'t, 'a, F> {
+    fn fmt_item(&self, f: &mut fmt::Formatter, ttlv: &Ttlv, depth: usize) -> fmt::Result {
+        write!(f, "{:1$}", "", depth * 2)?;
+        match (self.names)(ttlv.tag) {
+            Some(name) => write!(f, "{} (0x{:06X})", name, ttlv.tag)?,
+            None => write!(f, "0x{:06X}", ttlv.tag)?,
+        }
+        write!(f, " {:?}", ttlv.value.type_())?;
+        match &ttlv.value {
+            Value::Structure(children) => {
+                for c in children.iter() {
+                    f.write_str("\n")?;
+                    self.fmt_item(f, c, depth + 1)?;
+                }
+                Ok(())
+            }
+            value => {
+                f.write_str(": ")?;
+                fmt_value(f, value)
+            }
+        }
+    }
+}
+
+fn fmt_value(f: &mut fmt::Formatter, value: &Value) -> fmt::Result {
+    match value {
+        Value::Structure(children) => write!(f, "{} items", children.len()),
+        Value::Integer(val) => write!(f, "{}", val),
+        Value::LongInteger(val) => write!(f, "{}", val),
+        Value::BigInteger(val) => write!(f, "0x{}", Hex(val)),
+        Value::Enumeration(val) => write!(f, "0x{:08X}", val),
+        Value::Boolean(val) => write!(f, "{}", val),
+        Value::TextString(val) => write!(f, "{:?}", val),
+        Value::ByteString(val) => write!(f, "{}", Hex(val)),
+        Value::DateTime(val) => write!(f, "{}", val),
+        Value::Interval(val) => write!(f, "{}", val),
+        Value::DateTimeExtended(val) => write!(f, "{}", val),
+    }
+}
+
+impl<F: Fn(u32) -> Option<&'static str>> fmt::Display for Pretty<'_, '_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_item(f, self.ttlv, 0)
+    }
+}
+
+impl fmt::Display for Ttlv<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.pretty().fmt(f)
+    }
+}
+
+/// Displays raw TTLV bytes as an annotated hex dump: one line per header, broken into tag, type and length, followed by
+/// the value and padding bytes eight to a line. Unlike `Pretty`, it works on malformed input, reporting where parsing
+/// stopped and dumping the remaining bytes as-is.
+pub struct HexDump<'b, F> {
+    buf: &'b [u8],
+    names: F,
+}
+
+/// Dumps `buf` with hex tags.
+pub fn hex_dump(buf: &[u8]) -> HexDump<'_, TagNames> {
+    hex_dump_with(buf, |_| None)
+}
+
+/// Dumps `buf`, naming tags with `names` wherever it resolves them.
+pub fn hex_dump_with<F: Fn(u32) -> Option<&'static str>>(buf: &[u8], names: F) -> HexDump<'_, F> {
+    HexDump { buf, names }
+}
+
+/// Width of the hex column, so that notes line up for items at the same depth.
+const HEX_WIDTH: usize = 27;
+
+impl<'b, F: Fn(u32) -> Option<&'static str>> HexDump<'b, F> {
+    fn line(
+        &self,
+        f: &mut fmt::Formatter,
+        started: &mut bool,
+        offset: usize,
+        depth: usize,
+    ) -> fmt::Result {
+        if mem::replace(started, true) {
+            f.write_str("\n")?;
+        }
+        write!(f, "{:04X}  {:2$}", offset, "", depth * 2)
+    }
+
+    /// Writes up to eight bytes, leaving the cursor at the note column if `note` is set.
+    fn row(
+        &self,
+        f: &mut fmt::Formatter,
+        started: &mut bool,
+        offset: usize,
+        depth: usize,
+        note: bool,
+    ) -> fmt::Result {
+        self.line(f, started, offset, depth)?;
+        let bytes = &self.buf[offset..self.buf.len().min(offset + 8)];
+        for (i, b) in bytes.iter().enumerate() {
+            let sep = if i == 0 { "" } else { " " };
+            write!(f, "{}{:02X}", sep, b)?;
+        }
+        if note {
+            write!(
+                f,
+                "{:1$}",
+                "",
+                HEX_WIDTH + 2 - (bytes.len() * 3).saturating_sub(1)
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Reports why parsing stopped at `offset` and dumps everything from there to the end of the buffer.
+    fn stop(
+        &self,
+        f: &mut fmt::Formatter,
+        started: &mut bool,
+        offset: usize,
+        depth: usize,
+        kind: ErrorKind,
+    ) -> fmt::Result {
+        self.line(f, started, offset, depth)?;
+        write!(f, "error: {}, parsing stopped", kind)?;
+        for row in (offset..self.buf.len()).step_by(8) {
+            self.row(f, started, row, depth, false)?;
+        }
+        Ok(())
+    }
+
+    /// Dumps the items in `buf[start..end]`, returning `false` if parsing stopped.
+    fn fmt_items(
+        &self,
+        f: &mut fmt::Formatter,
+        started: &mut bool,
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> Result<bool, fmt::Error> {
+        let mut cursor = start;
+        while cursor < end {
+            if end - cursor < 8 {
+                self.stop(f, started, cursor, depth, ErrorKind::InsufficientBufferSize)?;
+                return Ok(false);
+            }
+            let header = &self.buf[cursor..cursor + 8];
+            let tag = header.cread_with::<u32>(0, BE) >> 8;
+            let type_ = Type::from_u8(header[3]);
+            let len = header.cread_with::<u32>(4, BE) as usize;
+
+            self.line(f, started, cursor, depth)?;
+            write!(
+                f,
+                "{:02X} {:02X} {:02X} | {:02X} | {:02X} {:02X} {:02X} {:02X}  ",
+                header[0],
+                header[1],
+                header[2],
+                header[3],
+                header[4],
+                header[5],
+                header[6],
+                header[7]
+            )?;
+            match (self.names)(tag) {
+                Some(name) => write!(f, "{} (0x{:06X})", name, tag)?,
+                None => write!(f, "0x{:06X}", tag)?,
+            }
+            match type_ {
+                Some(type_) => write!(f, " {}, length {}", type_.name(), len)?,
+                None => write!(f, " unknown type 0x{:02X}, length {}", header[3], len)?,
+            }
+
+            let type_ = match type_ {
+                Some(type_) => type_,
+                None => {
+                    self.stop(
+                        f,
+                        started,
+                        cursor + 8,
+                        depth + 1,
+                        ErrorKind::UnsupportedType,
+                    )?;
+                    return Ok(false);
+                }
+            };
+            let padded_len = padded_len(len);
+            if end - cursor - 8 < padded_len {
+                self.stop(
+                    f,
+                    started,
+                    cursor + 8,
+                    depth + 1,
+                    ErrorKind::InsufficientBufferSize,
+                )?;
+                return Ok(false);
+            }
+
+            if let (Type::Structure, MAX_DEPTH) = (type_, depth) {
+                self.stop(f, started, cursor + 8, depth + 1, ErrorKind::NestingTooDeep)?;
+                return Ok(false);
+            } else if let Type::Structure = type_ {
+                if !self.fmt_items(f, started, cursor + 8, cursor + 8 + len, depth + 1)? {
+                    return Ok(false);
+                }
+            } else {
+                let item = &self.buf[cursor..cursor + 8 + padded_len];
+                let value = decode_header(item, true)
+                    .and_then(|(tag, type_, len)| decode_primitive(item, tag, type_, len, true));
+                let fmt_note = |f: &mut fmt::Formatter| match &value {
+                    Ok(value) => fmt_value(f, value),
+                    Err(e) => write!(f, "error: {}", e.kind()),
+                };
+                if padded_len == 0 {
+                    // Empty values have no rows of their own, so the note gets a line to itself
+                    self.line(f, started, cursor + 8, depth + 1)?;
+                    write!(f, "{:1$}", "", HEX_WIDTH + 2)?;
+                    fmt_note(f)?;
+                }
+                let rows = (cursor + 8..cursor + 8 + padded_len).step_by(8);
+                let last = rows.len().saturating_sub(1);
+                for (i, row) in rows.enumerate() {
+                    let padding = padded_len - len;
+                    let note = i == 0 || (i == last && padding > 0);
+                    self.row(f, started, row, depth + 1, note)?;
+                    if i == 0 {
+                        fmt_note(f)?;
+                    }
+                    if i == last && padding > 0 {
+                        let sep = if i == 0 { ", " } else { "" };
+                        write!(f, "{}padding {}", sep, padding)?;
+                    }
+                }
+            }
+            cursor += 8 + padded_len;
+        }
+        Ok(true)
+    }
+}
+
+impl<F: Fn(u32) -> Option<&'static str>> fmt::Display for HexDump<'_, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_items(f, &mut false, 0, self.buf.len(), 0)
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Type;
+
+/// The kind of TTLV failure. Can be used for more targetted error-handling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    UnsupportedType,
+    TypeMismatch,
+    ChildNotFound,
+    InvalidTag,
+    InsufficientBufferSize,
+    CorruptUtf8,
+    InvalidLength,
+    NonZeroPadding,
+    InvalidBoolean,
+    Syntax,
+    UnknownName,
+    MessageTooLarge,
+    NestingTooDeep,
+}
+
+/// The common error type returned for all TTLV-related failures, along with where in the message it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    kind: ErrorKind,
+    offset: Option<usize>,
+    tag: Option<u32>,
+    expected: Option<Type>,
+    actual: Option<Type>,
+    #[cfg(feature = "alloc")]
+    path: Vec<u32>,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Self {
+        Error {
+            kind,
+            offset: None,
+            tag: None,
+            expected: None,
+            actual: None,
+            #[cfg(feature = "alloc")]
+            path: Vec::new(),
+        }
+    }
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+    pub(crate) fn with_tag(mut self, tag: u32) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+    pub(crate) fn with_types(mut self, expected: Type, actual: Type) -> Self {
+        self.expected = Some(expected);
+        self.actual = Some(actual);
+        self
+    }
+    /// Records a structure enclosing the item that failed, starting `offset` bytes into the structure's encoding.
+    pub(crate) fn within(mut self, tag: u32, offset: usize) -> Self {
+        #[cfg(feature = "alloc")]
+        self.path.insert(0, tag);
+        #[cfg(not(feature = "alloc"))]
+        let _ = tag;
+        self.offset = self.offset.map(|o| o + offset);
+        self
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+    /// Byte offset of the failing item from the start of the decoded buffer or text, for decode errors.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+    /// Tag of the failing item, or of the missing child for `ChildNotFound`.
+    pub fn tag(&self) -> Option<u32> {
+        self.tag
+    }
+    pub fn expected_type(&self) -> Option<Type> {
+        self.expected
+    }
+    pub fn actual_type(&self) -> Option<Type> {
+        self.actual
+    }
+    /// Tags of the structures enclosing the failing item, starting from the root. Always empty without the `alloc`
+    /// feature.
+    pub fn path(&self) -> &[u32] {
+        #[cfg(feature = "alloc")]
+        return &self.path;
+        #[cfg(not(feature = "alloc"))]
+        return &[];
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ErrorKind::UnsupportedType => "unsupported type",
+            ErrorKind::TypeMismatch => "type mismatch",
+            ErrorKind::ChildNotFound => "child not found",
+            ErrorKind::InvalidTag => "tag does not fit in 24 bits",
+            ErrorKind::InsufficientBufferSize => "insufficient buffer size",
+            ErrorKind::CorruptUtf8 => "text string is not valid UTF-8",
+            ErrorKind::InvalidLength => "invalid length for type",
+            ErrorKind::NonZeroPadding => "non-zero padding",
+            ErrorKind::InvalidBoolean => "boolean is neither 0 nor 1",
+            ErrorKind::Syntax => "syntax error",
+            ErrorKind::UnknownName => "unknown name",
+            ErrorKind::MessageTooLarge => "message exceeds maximum length",
+            ErrorKind::NestingTooDeep => "structures nested too deeply",
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let (Some(expected), Some(actual)) = (self.expected, self.actual) {
+            write!(f, " (expected {:?}, found {:?})", expected, actual)?;
+        }
+        if let Some(tag) = self.tag {
+            write!(f, " for tag 0x{:06X}", tag)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " at offset {}", offset)?;
+        }
+        if !self.path().is_empty() {
+            f.write_str(" in ")?;
+            for (i, tag) in self.path().iter().enumerate() {
+                let sep = if i == 0 { "" } else { "/" };
+                write!(f, "{}0x{:06X}", sep, tag)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+    }
+}
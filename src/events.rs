@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{
+    ttlv::{decode_header, decode_primitive},
+    util::padded_len,
+    Error, ErrorKind, Type, Value, MAX_DEPTH,
+};
+
+/// An item reached by `Events`, in encoding order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    /// A structure header. Its children follow, then a matching `EndStructure`.
+    StartStructure {
+        tag: u32,
+        len: usize,
+    },
+    /// A primitive item, never a `Value::Structure`.
+    Primitive {
+        tag: u32,
+        value: Value<'a>,
+    },
+    EndStructure,
+}
+
+/// Pull parser over the encoded bytes of a single top-level TTLV item, yielding events without building a tree or
+/// allocating. Iteration ends after the first error.
+#[derive(Debug, Clone)]
+pub struct Events<'a> {
+    buf: &'a [u8],
+    cursor: usize,
+    open: [(u32, usize); MAX_DEPTH], // Tag and end offset of each open structure
+    depth: usize,
+    done: bool,
+}
+
+impl<'a> Events<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Events {
+            buf,
+            cursor: 0,
+            open: [(0, 0); MAX_DEPTH],
+            depth: 0,
+            done: false,
+        }
+    }
+
+    /// Offset of the next event's item in the buffer. Once iteration has ended without error, this is the encoded length
+    /// of the top-level item.
+    pub fn offset(&self) -> usize {
+        self.cursor
+    }
+
+    fn fail(&mut self, e: Error) -> Option<Result<Event<'a>, Error>> {
+        self.done = true;
+        let e = self.open[..self.depth]
+            .iter()
+            .rev()
+            .fold(e.at(self.cursor), |e, (tag, _)| e.within(*tag, 0));
+        Some(Err(e))
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Result<Event<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let end = match self.depth {
+            0 if self.cursor > 0 => {
+                self.done = true;
+                return None;
+            }
+            0 => self.buf.len(),
+            depth => self.open[depth - 1].1,
+        };
+        if self.cursor >= end && self.depth > 0 {
+            self.depth -= 1;
+            self.cursor = padded_len(end);
+            return Some(Ok(Event::EndStructure));
+        }
+
+        let buf = &self.buf[self.cursor..end];
+        let (tag, type_, len) = match decode_header(buf, true) {
+            Ok(header) => header,
+            Err(e) => return self.fail(e),
+        };
+        if let Type::Structure = type_ {
+            if self.depth == MAX_DEPTH {
+                return self.fail(Error::new(ErrorKind::NestingTooDeep).with_tag(tag));
+            }
+            self.open[self.depth] = (tag, self.cursor + 8 + len);
+            self.depth += 1;
+            self.cursor += 8;
+            Some(Ok(Event::StartStructure { tag, len }))
+        } else {
+            match decode_primitive(buf, tag, type_, len, true) {
+                Ok(value) => {
+                    self.cursor += 8 + padded_len(len);
+                    Some(Ok(Event::Primitive { tag, value }))
+                }
+                Err(e) => self.fail(e),
+            }
+        }
+    }
+}
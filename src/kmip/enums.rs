@@ -0,0 +1,438 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::registry;
+use crate::{util::TryFromValue, *};
+
+/// Defines registries carried as `Enumeration` values, along with their conversions to and from `Value`.
+macro_rules! enumerations {
+    ($($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* })*) => {
+        $(
+            registry! {
+                $(#[$meta])*
+                #[allow(clippy::upper_case_acronyms)] // Names match the KMIP JSON and XML encodings
+                $name { $($variant = $value,)* }
+            }
+
+            impl<'a> From<$name> for Value<'a> {
+                fn from(val: $name) -> Self {
+                    Value::Enumeration(val.value())
+                }
+            }
+            impl<'a> TryFromValue<'a> for $name {
+                const TYPE: Type = Type::Enumeration;
+                fn try_from(value: &'a Value) -> Option<Self> {
+                    u32::try_from(value).map($name::from_value)
+                }
+                fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+                    u32::try_from_borrowed(value).map($name::from_value)
+                }
+            }
+            #[cfg(feature = "alloc")]
+            impl ToTtlv for $name {
+                fn to_ttlv(&self, tag: u32) -> Ttlv<'_> {
+                    Ttlv::new(tag, (*self).into())
+                }
+            }
+            #[cfg(feature = "alloc")]
+            impl<'a> FromTtlv<'a> for $name {
+                fn from_ttlv(ttlv: &'a Ttlv<'a>) -> Result<Self, Error> {
+                    ttlv.value()
+                }
+            }
+        )*
+
+        /// Looks up the name of an enumeration value carried under one of the KMIP tags with a known registry.
+        pub fn enumeration_name(tag: super::Tag, value: u32) -> Option<&'static str> {
+            match tag {
+                $(super::Tag::$name => $name::from_value(value).name(),)*
+                _ => None,
+            }
+        }
+
+        /// Looks up an enumeration value by name for one of the KMIP tags with a known registry.
+        pub fn enumeration_value(tag: super::Tag, name: &str) -> Option<u32> {
+            match tag {
+                $(super::Tag::$name => $name::from_name(name).map(|val| val.value()),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+enumerations! {
+    /// Operations defined by KMIP 1.0 through 2.1.
+    Operation {
+        Create = 0x00000001,
+        CreateKeyPair = 0x00000002,
+        Register = 0x00000003,
+        ReKey = 0x00000004,
+        DeriveKey = 0x00000005,
+        Certify = 0x00000006,
+        ReCertify = 0x00000007,
+        Locate = 0x00000008,
+        Check = 0x00000009,
+        Get = 0x0000000A,
+        GetAttributes = 0x0000000B,
+        GetAttributeList = 0x0000000C,
+        AddAttribute = 0x0000000D,
+        ModifyAttribute = 0x0000000E,
+        DeleteAttribute = 0x0000000F,
+        ObtainLease = 0x00000010,
+        GetUsageAllocation = 0x00000011,
+        Activate = 0x00000012,
+        Revoke = 0x00000013,
+        Destroy = 0x00000014,
+        Archive = 0x00000015,
+        Recover = 0x00000016,
+        Validate = 0x00000017,
+        Query = 0x00000018,
+        Cancel = 0x00000019,
+        Poll = 0x0000001A,
+        Notify = 0x0000001B,
+        Put = 0x0000001C,
+        ReKeyKeyPair = 0x0000001D,
+        DiscoverVersions = 0x0000001E,
+        Encrypt = 0x0000001F,
+        Decrypt = 0x00000020,
+        Sign = 0x00000021,
+        SignatureVerify = 0x00000022,
+        MAC = 0x00000023,
+        MACVerify = 0x00000024,
+        RNGRetrieve = 0x00000025,
+        RNGSeed = 0x00000026,
+        Hash = 0x00000027,
+        CreateSplitKey = 0x00000028,
+        JoinSplitKey = 0x00000029,
+        Import = 0x0000002A,
+        Export = 0x0000002B,
+        Log = 0x0000002C,
+        Login = 0x0000002D,
+        Logout = 0x0000002E,
+        DelegatedLogin = 0x0000002F,
+        AdjustAttribute = 0x00000030,
+        SetAttribute = 0x00000031,
+        SetEndpointRole = 0x00000032,
+        PKCS_11 = 0x00000033,
+        Interop = 0x00000034,
+        ReProvision = 0x00000035,
+        SetDefaults = 0x00000036,
+        SetConstraints = 0x00000037,
+        GetConstraints = 0x00000038,
+        QueryAsynchronousRequests = 0x00000039,
+        Process = 0x0000003A,
+        Ping = 0x0000003B,
+    }
+
+    /// Managed object types.
+    ObjectType {
+        Certificate = 0x00000001,
+        SymmetricKey = 0x00000002,
+        PublicKey = 0x00000003,
+        PrivateKey = 0x00000004,
+        SplitKey = 0x00000005,
+        Template = 0x00000006,
+        SecretData = 0x00000007,
+        OpaqueObject = 0x00000008,
+        PGPKey = 0x00000009,
+        CertificateRequest = 0x0000000A,
+    }
+
+    /// Cryptographic algorithms.
+    CryptographicAlgorithm {
+        DES = 0x00000001,
+        DES3 = 0x00000002,
+        AES = 0x00000003,
+        RSA = 0x00000004,
+        DSA = 0x00000005,
+        ECDSA = 0x00000006,
+        HMACSHA1 = 0x00000007,
+        HMACSHA224 = 0x00000008,
+        HMACSHA256 = 0x00000009,
+        HMACSHA384 = 0x0000000A,
+        HMACSHA512 = 0x0000000B,
+        HMACMD5 = 0x0000000C,
+        DH = 0x0000000D,
+        ECDH = 0x0000000E,
+        ECMQV = 0x0000000F,
+        Blowfish = 0x00000010,
+        Camellia = 0x00000011,
+        CAST5 = 0x00000012,
+        IDEA = 0x00000013,
+        MARS = 0x00000014,
+        RC2 = 0x00000015,
+        RC4 = 0x00000016,
+        RC5 = 0x00000017,
+        SKIPJACK = 0x00000018,
+        Twofish = 0x00000019,
+        EC = 0x0000001A,
+        OneTimePad = 0x0000001B,
+        ChaCha20 = 0x0000001C,
+        Poly1305 = 0x0000001D,
+        ChaCha20Poly1305 = 0x0000001E,
+        SHA3_224 = 0x0000001F,
+        SHA3_256 = 0x00000020,
+        SHA3_384 = 0x00000021,
+        SHA3_512 = 0x00000022,
+        HMACSHA3_224 = 0x00000023,
+        HMACSHA3_256 = 0x00000024,
+        HMACSHA3_384 = 0x00000025,
+        HMACSHA3_512 = 0x00000026,
+        SHAKE_128 = 0x00000027,
+        SHAKE_256 = 0x00000028,
+        ARIA = 0x00000029,
+        SEED = 0x0000002A,
+        SM2 = 0x0000002B,
+        SM3 = 0x0000002C,
+        SM4 = 0x0000002D,
+    }
+
+    /// Outcome of a batch item.
+    ResultStatus {
+        Success = 0x00000000,
+        OperationFailed = 0x00000001,
+        OperationPending = 0x00000002,
+        OperationUndone = 0x00000003,
+    }
+
+    /// Reasons a batch item failed.
+    ResultReason {
+        ItemNotFound = 0x00000001,
+        ResponseTooLarge = 0x00000002,
+        AuthenticationNotSuccessful = 0x00000003,
+        InvalidMessage = 0x00000004,
+        OperationNotSupported = 0x00000005,
+        MissingData = 0x00000006,
+        InvalidField = 0x00000007,
+        FeatureNotSupported = 0x00000008,
+        OperationCanceledByRequester = 0x00000009,
+        CryptographicFailure = 0x0000000A,
+        IllegalOperation = 0x0000000B,
+        PermissionDenied = 0x0000000C,
+        ObjectArchived = 0x0000000D,
+        IndexOutOfBounds = 0x0000000E,
+        ApplicationNamespaceNotSupported = 0x0000000F,
+        KeyFormatTypeNotSupported = 0x00000010,
+        KeyCompressionTypeNotSupported = 0x00000011,
+        EncodingOptionError = 0x00000012,
+        KeyValueNotPresent = 0x00000013,
+        AttestationRequired = 0x00000014,
+        AttestationFailed = 0x00000015,
+        Sensitive = 0x00000016,
+        NotExtractable = 0x00000017,
+        ObjectAlreadyExists = 0x00000018,
+        InvalidTicket = 0x00000019,
+        UsageLimitExceeded = 0x0000001A,
+        NumericRange = 0x0000001B,
+        InvalidDataType = 0x0000001C,
+        ReadOnlyAttribute = 0x0000001D,
+        MultiValuedAttribute = 0x0000001E,
+        UnsupportedAttribute = 0x0000001F,
+        AttributeInstanceNotFound = 0x00000020,
+        AttributeNotFound = 0x00000021,
+        AttributeReadOnly = 0x00000022,
+        AttributeSingleValued = 0x00000023,
+        BadCryptographicParameters = 0x00000024,
+        BadPassword = 0x00000025,
+        CodecError = 0x00000026,
+        GeneralFailure = 0x00000100,
+    }
+
+    /// Encodings of key material.
+    KeyFormatType {
+        Raw = 0x00000001,
+        Opaque = 0x00000002,
+        PKCS_1 = 0x00000003,
+        PKCS_8 = 0x00000004,
+        X_509 = 0x00000005,
+        ECPrivateKey = 0x00000006,
+        TransparentSymmetricKey = 0x00000007,
+        TransparentDSAPrivateKey = 0x00000008,
+        TransparentDSAPublicKey = 0x00000009,
+        TransparentRSAPrivateKey = 0x0000000A,
+        TransparentRSAPublicKey = 0x0000000B,
+        TransparentDHPrivateKey = 0x0000000C,
+        TransparentDHPublicKey = 0x0000000D,
+        TransparentECDSAPrivateKey = 0x0000000E,
+        TransparentECDSAPublicKey = 0x0000000F,
+        TransparentECDHPrivateKey = 0x00000010,
+        TransparentECDHPublicKey = 0x00000011,
+        TransparentECMQVPrivateKey = 0x00000012,
+        TransparentECMQVPublicKey = 0x00000013,
+        TransparentECPrivateKey = 0x00000014,
+        TransparentECPublicKey = 0x00000015,
+        PKCS_12 = 0x00000016,
+        PKCS_10 = 0x00000017,
+    }
+
+    /// Compression of elliptic curve public keys.
+    KeyCompressionType {
+        ECPublicKeyTypeUncompressed = 0x00000001,
+        ECPublicKeyTypeX9_62CompressedPrime = 0x00000002,
+        ECPublicKeyTypeX9_62CompressedChar2 = 0x00000003,
+        ECPublicKeyTypeX9_62Hybrid = 0x00000004,
+    }
+
+    /// Interpretation of a Name Value.
+    NameType {
+        UninterpretedTextString = 0x00000001,
+        URI = 0x00000002,
+    }
+
+    /// Lifecycle states of a managed object.
+    State {
+        PreActive = 0x00000001,
+        Active = 0x00000002,
+        Deactivated = 0x00000003,
+        Compromised = 0x00000004,
+        Destroyed = 0x00000005,
+        DestroyedCompromised = 0x00000006,
+    }
+
+    /// Relationships between managed objects.
+    LinkType {
+        CertificateLink = 0x00000101,
+        PublicKeyLink = 0x00000102,
+        PrivateKeyLink = 0x00000103,
+        DerivationBaseObjectLink = 0x00000104,
+        DerivedKeyLink = 0x00000105,
+        ReplacementObjectLink = 0x00000106,
+        ReplacedObjectLink = 0x00000107,
+        ParentLink = 0x00000108,
+        ChildLink = 0x00000109,
+        PreviousLink = 0x0000010A,
+        NextLink = 0x0000010B,
+        PKCS_12CertificateLink = 0x0000010C,
+        PKCS_12PasswordLink = 0x0000010D,
+        WrappingKeyLink = 0x0000010E,
+    }
+
+    /// Block cipher modes of operation.
+    BlockCipherMode {
+        CBC = 0x00000001,
+        ECB = 0x00000002,
+        PCBC = 0x00000003,
+        CFB = 0x00000004,
+        OFB = 0x00000005,
+        CTR = 0x00000006,
+        CMAC = 0x00000007,
+        CCM = 0x00000008,
+        GCM = 0x00000009,
+        CBCMAC = 0x0000000A,
+        XTS = 0x0000000B,
+        AESKeyWrapPadding = 0x0000000C,
+        NISTKeyWrap = 0x0000000D,
+        X9_102AESKW = 0x0000000E,
+        X9_102TDKW = 0x0000000F,
+        X9_102AKW1 = 0x00000010,
+        X9_102AKW2 = 0x00000011,
+        AEAD = 0x00000012,
+    }
+
+    /// Padding methods.
+    PaddingMethod {
+        None = 0x00000001,
+        OAEP = 0x00000002,
+        PKCS5 = 0x00000003,
+        SSL3 = 0x00000004,
+        Zeros = 0x00000005,
+        ANSIX9_23 = 0x00000006,
+        ISO10126 = 0x00000007,
+        PKCS1V1_5 = 0x00000008,
+        X9_31 = 0x00000009,
+        PSS = 0x0000000A,
+    }
+
+    /// Hashing algorithms.
+    HashingAlgorithm {
+        MD2 = 0x00000001,
+        MD4 = 0x00000002,
+        MD5 = 0x00000003,
+        SHA_1 = 0x00000004,
+        SHA_224 = 0x00000005,
+        SHA_256 = 0x00000006,
+        SHA_384 = 0x00000007,
+        SHA_512 = 0x00000008,
+        RIPEMD_160 = 0x00000009,
+        Tiger = 0x0000000A,
+        Whirlpool = 0x0000000B,
+        SHA_512_224 = 0x0000000C,
+        SHA_512_256 = 0x0000000D,
+        SHA3_224 = 0x0000000E,
+        SHA3_256 = 0x0000000F,
+        SHA3_384 = 0x00000010,
+        SHA3_512 = 0x00000011,
+    }
+
+    /// Kinds of credential in an Authentication structure.
+    CredentialType {
+        UsernameAndPassword = 0x00000001,
+        Device = 0x00000002,
+        Attestation = 0x00000003,
+        OneTimePassword = 0x00000004,
+        HashedPassword = 0x00000005,
+        Ticket = 0x00000006,
+    }
+
+    /// Reasons for revoking a managed object.
+    RevocationReasonCode {
+        Unspecified = 0x00000001,
+        KeyCompromise = 0x00000002,
+        CACompromise = 0x00000003,
+        AffiliationChanged = 0x00000004,
+        Superseded = 0x00000005,
+        CessationOfOperation = 0x00000006,
+        PrivilegeWithdrawn = 0x00000007,
+    }
+
+    /// Kinds of secret data.
+    SecretDataType {
+        Password = 0x00000001,
+        Seed = 0x00000002,
+    }
+
+    /// Certificate formats.
+    CertificateType {
+        X_509 = 0x00000001,
+        PGP = 0x00000002,
+    }
+
+    /// What a server does with the rest of a batch after an item fails.
+    BatchErrorContinuationOption {
+        Continue = 0x00000001,
+        Stop = 0x00000002,
+        Undo = 0x00000003,
+    }
+
+    /// Information requested by a Query operation.
+    QueryFunction {
+        QueryOperations = 0x00000001,
+        QueryObjects = 0x00000002,
+        QueryServerInformation = 0x00000003,
+        QueryApplicationNamespaces = 0x00000004,
+        QueryExtensionList = 0x00000005,
+        QueryExtensionMap = 0x00000006,
+        QueryAttestationTypes = 0x00000007,
+        QueryRNGs = 0x00000008,
+        QueryValidations = 0x00000009,
+        QueryProfiles = 0x0000000A,
+        QueryCapabilities = 0x0000000B,
+        QueryClientRegistrationMethods = 0x0000000C,
+        QueryDefaultsInformation = 0x0000000D,
+        QueryStorageProtectionMasks = 0x0000000E,
+    }
+
+    /// Key wrapping methods.
+    WrappingMethod {
+        Encrypt = 0x00000001,
+        MACSign = 0x00000002,
+        EncryptThenMACSign = 0x00000003,
+        MACSignThenEncrypt = 0x00000004,
+        TR_31 = 0x00000005,
+    }
+
+    /// Encoding of wrapped key material.
+    EncodingOption {
+        NoEncoding = 0x00000001,
+        TTLVEncoding = 0x00000002,
+    }
+}
@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The KMIP JSON encoding from the KMIP profiles, e.g. `{"tag":"ProtocolVersionMajor","type":"Integer","value":2}`.
+//! Enumerations and masks in an Attribute Value are named after the attribute in the sibling Attribute Name, as in
+//! XML, with mask names separated by `|`, e.g. `"Decrypt|Encrypt"`.
+
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{convert::TryFrom, fmt, str::from_utf8, str::FromStr};
+
+use super::text::*;
+use crate::{
+    util::{big_integer_len, is_negative, Hex},
+    Error, ErrorKind, OwnedTtlv, Ttlv, Type, Value, MAX_DEPTH,
+};
+
+/// Largest magnitude a JSON number can carry without losing precision in common parsers; larger Long Integers are
+/// written as hex strings.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Deepest nesting of objects and arrays accepted, enough for `MAX_DEPTH` structures around a primitive.
+const MAX_NESTING: usize = 2 * (MAX_DEPTH + 1);
+
+/// Displays a TTLV tree in the KMIP JSON encoding.
+pub struct Json<'t, 'a>(pub &'t Ttlv<'a>);
+
+impl fmt::Display for Json<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_item(f, self.0, None)
+    }
+}
+
+fn fmt_item(f: &mut fmt::Formatter, ttlv: &Ttlv, attribute: Option<u32>) -> fmt::Result {
+    write!(
+        f,
+        "{{\"tag\":\"{}\",\"type\":\"{}\",\"value\":",
+        TagText(ttlv.tag),
+        ttlv.value.type_().name()
+    )?;
+    let value_tag = value_tag(ttlv.tag, attribute);
+    match &ttlv.value {
+        Value::Structure(children) => {
+            f.write_str("[")?;
+            let mut attribute = None;
+            for (i, c) in children.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                fmt_item(f, c, attribute)?;
+                attribute = attribute_tag(c);
+            }
+            f.write_str("]")?;
+        }
+        Value::Integer(val) => {
+            let mask = MaskText {
+                tag: value_tag,
+                mask: *val,
+                separator: '|',
+            };
+            if mask.is_named() {
+                write!(f, "\"{}\"", mask)?
+            } else {
+                write!(f, "{}", val)?
+            }
+        }
+        Value::LongInteger(val) if val.unsigned_abs() <= MAX_SAFE_INTEGER => write!(f, "{}", val)?,
+        Value::LongInteger(val) => write!(f, "\"0x{:016X}\"", val)?,
+        Value::BigInteger(val) => {
+            let sign = if is_negative(val) { "FF" } else { "00" };
+            f.write_str("\"0x")?;
+            for _ in val.len()..big_integer_len(val.len()) {
+                f.write_str(sign)?;
+            }
+            write!(f, "{}\"", Hex(val))?;
+        }
+        Value::Enumeration(val) => write!(f, "\"{}\"", EnumerationText(value_tag, *val))?,
+        Value::Boolean(val) => write!(f, "{}", val)?,
+        Value::TextString(val) => write_string(f, val)?,
+        Value::ByteString(val) => write!(f, "\"{}\"", Hex(val))?,
+        Value::DateTime(val) => write!(
+            f,
+            "\"{}\"",
+            DateTimeText {
+                seconds: *val,
+                micros: None
+            }
+        )?,
+        Value::Interval(val) => write!(f, "{}", val)?,
+        Value::DateTimeExtended(val) => write!(f, "\"{}\"", DateTimeText::from_micros(*val))?,
+    }
+    f.write_str("}")
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/// Encodes a TTLV tree in the KMIP JSON encoding.
+pub fn to_string(ttlv: &Ttlv) -> String {
+    use alloc::string::ToString;
+    Json(ttlv).to_string()
+}
+
+/// Decodes a TTLV tree from the KMIP JSON encoding. Error offsets are byte positions in `json`.
+pub fn from_str(json: &str) -> Result<OwnedTtlv, Error> {
+    let mut parser = Parser {
+        text: json,
+        pos: 0,
+        depth: 0,
+    };
+    let value = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos != json.len() {
+        return Err(parser.error());
+    }
+    to_ttlv(&value, None)
+}
+
+#[derive(Debug)]
+enum JsonValue<'s> {
+    Null,
+    Bool(bool),
+    Number(&'s str),
+    String(Cow<'s, str>),
+    Array(Vec<JsonValue<'s>>),
+    Object(usize, Vec<(Cow<'s, str>, JsonValue<'s>)>),
+}
+
+struct Parser<'s> {
+    text: &'s str,
+    pos: usize,
+    depth: usize, // Objects and arrays enclosing the cursor
+}
+
+impl<'s> Parser<'s> {
+    fn error(&self) -> Error {
+        Error::new(ErrorKind::Syntax).at(self.pos)
+    }
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).copied()
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.pos += 1;
+        }
+    }
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        if self.text[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue<'s>, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => self.nested(Self::parse_object),
+            Some(b'[') => self.nested(Self::parse_array),
+            Some(b'"') => self.parse_string().map(JsonValue::String),
+            Some(b't') => self.expect("true").map(|_| JsonValue::Bool(true)),
+            Some(b'f') => self.expect("false").map(|_| JsonValue::Bool(false)),
+            Some(b'n') => self.expect("null").map(|_| JsonValue::Null),
+            Some(b'-' | b'0'..=b'9') => {
+                let start = self.pos;
+                while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+                    self.pos += 1;
+                }
+                Ok(JsonValue::Number(&self.text[start..self.pos]))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Parses an object or array, bounding the recursion so that hostile input can't exhaust the stack.
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<JsonValue<'s>, Error>,
+    ) -> Result<JsonValue<'s>, Error> {
+        if self.depth == MAX_NESTING {
+            return Err(Error::new(ErrorKind::NestingTooDeep).at(self.pos));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue<'s>, Error> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue<'s>, Error> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut members = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(start, members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error());
+            }
+            let name = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(":")?;
+            members.push((name, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(start, members));
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<Cow<'s, str>, Error> {
+        self.pos += 1;
+        let start = self.pos;
+        // Borrow the string as-is unless it contains escapes
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(Cow::Borrowed(&self.text[start..self.pos - 1]));
+                }
+                Some(b'\\') => break,
+                Some(b) if b < 0x20 => return Err(self.error()),
+                Some(_) => self.pos += 1,
+                None => return Err(self.error()),
+            }
+        }
+        let mut s = String::from(&self.text[start..self.pos]);
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(Cow::Owned(s));
+                }
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let escaped = match self.peek() {
+                        Some(b'"') => '"',
+                        Some(b'\\') => '\\',
+                        Some(b'/') => '/',
+                        Some(b'b') => '\u{8}',
+                        Some(b'f') => '\u{c}',
+                        Some(b'n') => '\n',
+                        Some(b'r') => '\r',
+                        Some(b't') => '\t',
+                        Some(b'u') => {
+                            let high = self.parse_hex4()?;
+                            let c = if (0xD800..0xDC00).contains(&high) {
+                                self.expect("\\")?;
+                                let low = self.parse_hex4()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error());
+                                }
+                                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                            } else {
+                                high
+                            };
+                            s.push(char::from_u32(c).ok_or_else(|| self.error())?);
+                            continue;
+                        }
+                        _ => return Err(self.error()),
+                    };
+                    self.pos += 1;
+                    s.push(escaped);
+                }
+                Some(b) if b < 0x20 => return Err(self.error()),
+                Some(_) => {
+                    let c = self.text[self.pos..]
+                        .chars()
+                        .next()
+                        .ok_or_else(|| self.error())?;
+                    self.pos += c.len_utf8();
+                    s.push(c);
+                }
+                None => return Err(self.error()),
+            }
+        }
+    }
+
+    /// Parses `uXXXX`, leaving the cursor after the last hex digit.
+    fn parse_hex4(&mut self) -> Result<u32, Error> {
+        self.expect("u")?;
+        let digits = self
+            .text
+            .as_bytes()
+            .get(self.pos..self.pos + 4)
+            .and_then(|digits| from_utf8(digits).ok())
+            .ok_or_else(|| self.error())?;
+        let val = u32::from_str_radix(digits, 16).map_err(|_| self.error())?;
+        self.pos += 4;
+        Ok(val)
+    }
+}
+
+/// Converts a JSON object to an item, with `attribute` from a preceding Attribute Name.
+fn to_ttlv(json: &JsonValue, attribute: Option<u32>) -> Result<OwnedTtlv, Error> {
+    let (pos, members) = match json {
+        JsonValue::Object(pos, members) => (*pos, members),
+        _ => return Err(Error::new(ErrorKind::Syntax)),
+    };
+    let member = |name: &str| members.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+    fn string<'j>(value: Option<&'j JsonValue>, pos: usize) -> Result<&'j str, Error> {
+        match value {
+            Some(JsonValue::String(s)) => Ok(s),
+            _ => Err(Error::new(ErrorKind::Syntax).at(pos)),
+        }
+    }
+
+    let tag = parse_tag(string(member("tag"), pos)?).map_err(|e| e.at(pos))?;
+    let type_ = match member("type") {
+        None => Type::Structure,
+        type_ => Type::from_name(string(type_, pos)?)
+            .ok_or_else(|| Error::new(ErrorKind::UnknownName).at(pos).with_tag(tag))?,
+    };
+    let value =
+        member("value").ok_or_else(|| Error::new(ErrorKind::Syntax).at(pos).with_tag(tag))?;
+    let value = match (type_, value) {
+        (Type::Structure, JsonValue::Array(items)) => {
+            let mut children = Vec::with_capacity(items.len());
+            let mut attribute = None;
+            for item in items {
+                let child = to_ttlv(item, attribute).map_err(|e| e.within(tag, 0))?;
+                attribute = attribute_tag(&child);
+                children.push(child);
+            }
+            Value::Structure(children.into())
+        }
+        (type_, value) => to_primitive(value_tag(tag, attribute), type_, value)
+            .map_err(|e| e.at(pos).with_tag(tag))?,
+    };
+    Ok(Ttlv::new(tag, value))
+}
+
+/// Parses a decimal JSON number, or a hex string holding the value's bits.
+fn number<T: FromStr>(json: &JsonValue, from_bits: fn(u64) -> Option<T>) -> Result<T, Error> {
+    match json {
+        JsonValue::Number(n) => n.parse().ok(),
+        JsonValue::String(s) => from_bits(parse_hex_int(s)?),
+        _ => None,
+    }
+    .ok_or_else(|| Error::new(ErrorKind::Syntax))
+}
+
+fn to_primitive(tag: u32, type_: Type, json: &JsonValue) -> Result<Value<'static>, Error> {
+    let i32_bits = |bits| u32::try_from(bits).ok().map(|bits| bits as i32);
+    let u32_bits = |bits| u32::try_from(bits).ok();
+    let i64_bits = |bits| Some(bits as i64);
+    let date_time = |json: &JsonValue| match json {
+        JsonValue::String(s) if !s.starts_with("0x") => DateTimeText::parse(s),
+        json => number(json, i64_bits).map(|seconds| DateTimeText {
+            seconds,
+            micros: None,
+        }),
+    };
+    Ok(match (type_, json) {
+        (Type::Integer, JsonValue::String(s)) if !s.starts_with("0x") => {
+            Value::Integer(parse_mask(tag, s)?)
+        }
+        (Type::Integer, json) => Value::Integer(number(json, i32_bits)?),
+        (Type::LongInteger, json) => Value::LongInteger(number(json, i64_bits)?),
+        (Type::BigInteger, JsonValue::String(s)) => {
+            let digits = s
+                .strip_prefix("0x")
+                .ok_or_else(|| Error::new(ErrorKind::Syntax))?;
+            Value::BigInteger(parse_hex_bytes(digits)?.into())
+        }
+        (Type::BigInteger, json) => {
+            Value::BigInteger(number(json, i64_bits)?.to_be_bytes().to_vec().into())
+        }
+        (Type::Enumeration, JsonValue::String(s)) => Value::Enumeration(parse_enumeration(tag, s)?),
+        (Type::Enumeration, json) => Value::Enumeration(number(json, u32_bits)?),
+        (Type::Boolean, JsonValue::Bool(val)) => Value::Boolean(*val),
+        (Type::Boolean, json) => match number(json, i64_bits)? {
+            0 => Value::Boolean(false),
+            1 => Value::Boolean(true),
+            _ => return Err(Error::new(ErrorKind::InvalidBoolean)),
+        },
+        (Type::TextString, JsonValue::String(s)) => {
+            Value::TextString(String::from(s.as_ref()).into())
+        }
+        (Type::ByteString, JsonValue::String(s)) => Value::ByteString(parse_hex_bytes(s)?.into()),
+        (Type::DateTime, json) => Value::DateTime(date_time(json)?.seconds),
+        (Type::Interval, json) => Value::Interval(number(json, u32_bits)?),
+        (Type::DateTimeExtended, JsonValue::String(s)) if !s.starts_with("0x") => {
+            Value::DateTimeExtended(DateTimeText::parse(s)?.to_micros())
+        }
+        (Type::DateTimeExtended, json) => Value::DateTimeExtended(number(json, i64_bits)?),
+        _ => return Err(Error::new(ErrorKind::Syntax)),
+    })
+}
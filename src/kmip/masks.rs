@@ -0,0 +1,87 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::registry;
+
+/// Defines registries of the bits carried together in `Integer` masks.
+macro_rules! masks {
+    ($($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* })*) => {
+        $(
+            registry! {
+                $(#[$meta])*
+                #[allow(clippy::upper_case_acronyms)] // Names match the KMIP JSON and XML encodings
+                $name { $($variant = $value,)* }
+            }
+        )*
+
+        /// Looks up the name of a single mask bit carried under one of the KMIP tags with a known mask registry.
+        pub fn mask_name(tag: super::Tag, bit: u32) -> Option<&'static str> {
+            match tag {
+                $(super::Tag::$name => $name::from_value(bit).name(),)*
+                _ => None,
+            }
+        }
+
+        /// Looks up a mask bit by name for one of the KMIP tags with a known mask registry.
+        pub fn mask_value(tag: super::Tag, name: &str) -> Option<u32> {
+            match tag {
+                $(super::Tag::$name => $name::from_name(name).map(|bit| bit.value()),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+masks! {
+    /// Cryptographic operations a managed object may be used for.
+    CryptographicUsageMask {
+        Sign = 0x00000001,
+        Verify = 0x00000002,
+        Encrypt = 0x00000004,
+        Decrypt = 0x00000008,
+        WrapKey = 0x00000010,
+        UnwrapKey = 0x00000020,
+        Export = 0x00000040,
+        MACGenerate = 0x00000080,
+        MACVerify = 0x00000100,
+        DeriveKey = 0x00000200,
+        ContentCommitment = 0x00000400,
+        KeyAgreement = 0x00000800,
+        CertificateSign = 0x00001000,
+        CRLSign = 0x00002000,
+        GenerateCryptogram = 0x00004000,
+        ValidateCryptogram = 0x00008000,
+        TranslateEncrypt = 0x00010000,
+        TranslateDecrypt = 0x00020000,
+        TranslateWrap = 0x00040000,
+        TranslateUnwrap = 0x00080000,
+        Authenticate = 0x00100000,
+        Unrestricted = 0x00200000,
+        FPEEncrypt = 0x00400000,
+        FPEDecrypt = 0x00800000,
+    }
+
+    /// Where a Locate operation looks for objects.
+    StorageStatusMask {
+        OnlineStorage = 0x00000001,
+        ArchivalStorage = 0x00000002,
+        DestroyedStorage = 0x00000004,
+    }
+
+    /// How managed objects are protected in storage.
+    ProtectionStorageMask {
+        Software = 0x00000001,
+        Hardware = 0x00000002,
+        OnProcessor = 0x00000004,
+        OnSystem = 0x00000008,
+        OffSystem = 0x00000010,
+        Hypervisor = 0x00000020,
+        OperatingSystem = 0x00000040,
+        Container = 0x00000080,
+        OnPremises = 0x00000100,
+        OffPremises = 0x00000200,
+        SelfManaged = 0x00000400,
+        Outsourced = 0x00000800,
+        Validated = 0x00001000,
+        SameJurisdiction = 0x00002000,
+    }
+}
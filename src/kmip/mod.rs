@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Registries of the tags and values defined by the KMIP specification, versions 1.0 through 2.1.
+
+#[cfg(feature = "alloc")]
+pub mod json;
+#[cfg(feature = "alloc")]
+pub mod xml;
+
+mod enums;
+mod masks;
+mod tag;
+#[cfg(feature = "alloc")]
+mod text;
+
+pub use self::enums::*;
+pub use self::masks::*;
+pub use self::tag::Tag;
+
+/// Names KMIP tags, e.g. for `Ttlv::pretty_with(kmip::tag_name)`.
+pub fn tag_name(tag: u32) -> Option<&'static str> {
+    Tag::from_value(tag).name()
+}
+
+/// Defines an enum of named KMIP values, with a fallback for values outside the specification so that decoding never
+/// fails on them.
+///
+/// Variants are named as in the KMIP JSON and XML encodings, which normalize the specification's names: punctuation
+/// before a letter separates words, other punctuation becomes `_`, leading digits move to the end, and words are joined
+/// with their first letter capitalized. So `SHA-256` is `SHA_256`, `X.509 Certificate Identifier` is
+/// `X_509CertificateIdentifier`, `3DES` is `DES3` and `MAC/sign` is `MACSign`.
+macro_rules! registry {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident = $value:literal,)* }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(non_camel_case_types)] // Variants are named as in the KMIP text encodings, e.g. `SHA_256`
+        pub enum $name {
+            $($variant,)*
+            /// A value not defined by the KMIP specification, such as a vendor extension.
+            Unknown(u32),
+        }
+
+        impl $name {
+            pub fn from_value(value: u32) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Unknown(value),
+                }
+            }
+            pub fn value(&self) -> u32 {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(value) => *value,
+                }
+            }
+            /// Looks up a value by the name used in the KMIP JSON and XML encodings, e.g. `ProtocolVersion`.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($variant) => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+            /// The name used in the KMIP JSON and XML encodings, or `None` for unknown values.
+            pub fn name(&self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some(stringify!($variant)),)*
+                    $name::Unknown(_) => None,
+                }
+            }
+        }
+    };
+}
+use registry;
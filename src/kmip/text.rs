@@ -0,0 +1,314 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Textual forms of tags and values shared by the KMIP JSON and XML encodings.
+
+use alloc::{string::String, vec::Vec};
+use core::{convert::TryFrom, fmt};
+
+use super::{enumeration_name, enumeration_value, mask_name, mask_value, Tag};
+use crate::{ttlv::MAX_TAG, Error, ErrorKind, Ttlv, Value};
+
+/// Formats a tag as its KMIP name, or as `0x` followed by 6 hex digits if it has none.
+pub struct TagText(pub u32);
+
+impl fmt::Display for TagText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match Tag::from_value(self.0).name() {
+            Some(name) => f.write_str(name),
+            None => write!(f, "0x{:06X}", self.0),
+        }
+    }
+}
+
+pub fn parse_tag(text: &str) -> Result<u32, Error> {
+    if text.starts_with("0x") {
+        match u32::try_from(parse_hex_int(text)?) {
+            Ok(tag) if tag <= MAX_TAG => Ok(tag),
+            _ => Err(Error::new(ErrorKind::InvalidTag)),
+        }
+    } else {
+        Tag::from_name(text)
+            .map(|tag| tag.value())
+            .ok_or_else(|| Error::new(ErrorKind::UnknownName))
+    }
+}
+
+/// Formats an enumeration as its name in the registry for `tag`, or as `0x` followed by 8 hex digits if it has none.
+pub struct EnumerationText(pub u32, pub u32);
+
+impl fmt::Display for EnumerationText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match enumeration_name(Tag::from_value(self.0), self.1) {
+            Some(name) => f.write_str(name),
+            None => write!(f, "0x{:08X}", self.1),
+        }
+    }
+}
+
+pub fn parse_enumeration(tag: u32, text: &str) -> Result<u32, Error> {
+    if text.starts_with("0x") {
+        u32::try_from(parse_hex_int(text)?).map_err(|_| Error::new(ErrorKind::Syntax))
+    } else {
+        enumeration_value(Tag::from_value(tag), text)
+            .ok_or_else(|| Error::new(ErrorKind::UnknownName))
+    }
+}
+
+/// Normalizes a name from the KMIP specification, such as `Cryptographic Usage Mask` in an Attribute Name, to its form
+/// in the JSON and XML encodings, e.g. `CryptographicUsageMask`. See `registry!` for the rules.
+pub fn normalize_name(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut spaced = String::with_capacity(name.len());
+    for (i, c) in chars.iter().enumerate() {
+        match c {
+            '(' | ')' => spaced.push(' '),
+            c if c.is_alphanumeric() || *c == '_' || c.is_whitespace() => spaced.push(*c),
+            _ if chars.get(i + 1).is_some_and(|next| next.is_alphabetic()) => spaced.push(' '),
+            _ => spaced.push('_'),
+        }
+    }
+
+    let spaced = spaced.trim();
+    let digits = spaced.len()
+        - spaced
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    let mut normalized = String::with_capacity(spaced.len());
+    for (i, word) in spaced[digits..].split_whitespace().enumerate() {
+        let mut chars = word.chars();
+        if i > 0 {
+            normalized.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        }
+        normalized.push_str(chars.as_str());
+    }
+    normalized.push_str(&spaced[..digits]);
+    normalized
+}
+
+/// The tag naming an Attribute Value's enumerations and masks, from an Attribute Name preceding it.
+pub fn attribute_tag(ttlv: &Ttlv) -> Option<u32> {
+    match (Tag::from_value(ttlv.tag), &ttlv.value) {
+        (Tag::AttributeName, Value::TextString(name)) => {
+            Tag::from_name(&normalize_name(name)).map(|tag| tag.value())
+        }
+        _ => None,
+    }
+}
+
+/// The tag whose registries name the value of an item: the attribute's for an Attribute Value following an Attribute
+/// Name, otherwise the item's own.
+pub fn value_tag(tag: u32, attribute: Option<u32>) -> u32 {
+    match Tag::from_value(tag) {
+        Tag::AttributeValue => attribute.unwrap_or(tag),
+        _ => tag,
+    }
+}
+
+/// Formats an Integer as the names of its bits in the mask registry for `tag`, or in decimal if it has none or some of
+/// its bits are unnamed.
+pub struct MaskText {
+    pub tag: u32,
+    pub mask: i32,
+    pub separator: char,
+}
+
+impl MaskText {
+    /// Whether every bit of the mask has a name, so that it is written as names.
+    pub fn is_named(&self) -> bool {
+        self.mask != 0
+            && self
+                .bits()
+                .all(|bit| mask_name(Tag::from_value(self.tag), bit).is_some())
+    }
+    fn bits(&self) -> impl Iterator<Item = u32> {
+        let mask = self.mask as u32;
+        (0..32).map(|i| 1 << i).filter(move |bit| mask & bit != 0)
+    }
+}
+
+impl fmt::Display for MaskText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.is_named() {
+            return write!(f, "{}", self.mask);
+        }
+        for (i, bit) in self.bits().enumerate() {
+            if i > 0 {
+                write!(f, "{}", self.separator)?;
+            }
+            let name = mask_name(Tag::from_value(self.tag), bit).unwrap_or_default();
+            f.write_str(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses mask bit names from the registry for `tag`, separated by spaces as in XML or `|` as in JSON.
+pub fn parse_mask(tag: u32, text: &str) -> Result<i32, Error> {
+    let mut mask = 0;
+    let mut names = text
+        .split(|c: char| c.is_whitespace() || c == '|')
+        .filter(|name| !name.is_empty())
+        .peekable();
+    if names.peek().is_none() {
+        return Err(Error::new(ErrorKind::Syntax));
+    }
+    for name in names {
+        mask |= mask_value(Tag::from_value(tag), name)
+            .ok_or_else(|| Error::new(ErrorKind::UnknownName))?;
+    }
+    Ok(mask as i32)
+}
+
+/// Parses `0x`-prefixed hex digits as an unsigned integer of up to 64 bits.
+pub fn parse_hex_int(text: &str) -> Result<u64, Error> {
+    text.strip_prefix("0x")
+        .filter(|digits| !digits.is_empty() && digits.len() <= 16)
+        .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| Error::new(ErrorKind::Syntax))
+}
+
+/// Parses an even number of hex digits as bytes.
+pub fn parse_hex_bytes(digits: &str) -> Result<Vec<u8>, Error> {
+    if !digits.len().is_multiple_of(2) {
+        return Err(Error::new(ErrorKind::Syntax));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            digits
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| Error::new(ErrorKind::Syntax))
+        })
+        .collect()
+}
+
+/// Formats seconds (or microseconds) since the Unix epoch as an ISO 8601 date and time in UTC. Times outside the years
+/// 0000 to 9999 don't fit its four-digit year, so they are written as `0x` followed by the value's 16 hex digits.
+pub struct DateTimeText {
+    pub seconds: i64,
+    pub micros: Option<u32>,
+}
+
+impl fmt::Display for DateTimeText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (year, month, day) = civil_from_days(self.seconds.div_euclid(86400));
+        if !(0..=9999).contains(&year) {
+            let val = match self.micros {
+                Some(_) => self.to_micros(),
+                None => self.seconds,
+            };
+            return write!(f, "0x{:016X}", val);
+        }
+        let secs = self.seconds.rem_euclid(86400);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )?;
+        if let Some(micros) = self.micros {
+            write!(f, ".{:06}", micros)?;
+        }
+        f.write_str("+00:00")
+    }
+}
+
+impl DateTimeText {
+    pub fn from_micros(micros: i64) -> Self {
+        DateTimeText {
+            seconds: micros.div_euclid(1_000_000),
+            micros: Some(micros.rem_euclid(1_000_000) as u32),
+        }
+    }
+    pub fn to_micros(&self) -> i64 {
+        // The product alone can overflow for values near i64::MIN that the sum brings back in range
+        (i128::from(self.seconds) * 1_000_000 + i128::from(self.micros.unwrap_or(0))) as i64
+    }
+
+    /// Parses `YYYY-MM-DDTHH:MM:SS[.ffffff](Z|±HH:MM)`.
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let err = || Error::new(ErrorKind::Syntax);
+        let num = |range: core::ops::Range<usize>| -> Result<i64, Error> {
+            let digits = text.get(range).ok_or_else(err)?;
+            if !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(err());
+            }
+            digits.parse().map_err(|_| err())
+        };
+        let bytes = text.as_bytes();
+        if bytes.len() < 20
+            || bytes[4] != b'-'
+            || bytes[7] != b'-'
+            || bytes[10] != b'T'
+            || bytes[13] != b':'
+            || bytes[16] != b':'
+        {
+            return Err(err());
+        }
+        let (month, day) = (num(5..7)?, num(8..10)?);
+        let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+        if !(1..=12).contains(&month)
+            || !(1..=31).contains(&day)
+            || hour > 23
+            || minute > 59
+            || second > 60
+        {
+            return Err(err());
+        }
+        let mut seconds =
+            days_from_civil(num(0..4)?, month, day) * 86400 + hour * 3600 + minute * 60 + second;
+
+        let mut rest = &text[19..];
+        let mut micros = None;
+        if let Some(fraction) = rest.strip_prefix('.') {
+            let len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if len == 0 || len > 6 {
+                return Err(err());
+            }
+            micros = Some(
+                fraction[..len].parse::<u32>().map_err(|_| err())? * 10u32.pow(6 - len as u32),
+            );
+            rest = &fraction[len..];
+        }
+        match rest.as_bytes() {
+            b"Z" => {}
+            [sign @ (b'+' | b'-'), _, _, b':', _, _] => {
+                let offset = num(text.len() - 5..text.len() - 3)? * 3600
+                    + num(text.len() - 2..text.len())? * 60;
+                seconds -= if *sign == b'+' { offset } else { -offset };
+            }
+            _ => return Err(err()),
+        }
+        Ok(DateTimeText { seconds, micros })
+    }
+}
+
+// Conversions between days since the Unix epoch and proleptic Gregorian dates, from
+// <http://howardhinnant.github.io/date_algorithms.html>.
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
@@ -0,0 +1,353 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The KMIP XML encoding from the KMIP profiles, e.g. `<ProtocolVersionMajor type="Integer" value="2"/>`. Tags
+//! without a KMIP name are written as `<TTLV tag="0x540001" ...>`. Enumerations and masks in an Attribute Value are
+//! named after the attribute in the sibling Attribute Name, e.g. `AES` for `Cryptographic Algorithm`.
+
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{convert::TryFrom, fmt, str::FromStr};
+
+use super::{text::*, Tag};
+use crate::{
+    util::{big_integer_len, is_negative, Hex},
+    Error, ErrorKind, OwnedTtlv, Ttlv, Type, Value, MAX_DEPTH,
+};
+
+/// Displays a TTLV tree in the KMIP XML encoding, one element per line.
+pub struct Xml<'t, 'a>(pub &'t Ttlv<'a>);
+
+impl fmt::Display for Xml<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_element(f, self.0, 0, None)
+    }
+}
+
+fn fmt_element(
+    f: &mut fmt::Formatter,
+    ttlv: &Ttlv,
+    depth: usize,
+    attribute: Option<u32>,
+) -> fmt::Result {
+    write!(f, "{:1$}", "", depth * 2)?;
+    let name = Tag::from_value(ttlv.tag).name();
+    match name {
+        Some(name) => write!(f, "<{}", name)?,
+        None => write!(f, "<TTLV tag=\"0x{:06X}\"", ttlv.tag)?,
+    }
+    if let Value::Structure(children) = &ttlv.value {
+        if children.is_empty() {
+            return f.write_str("/>");
+        }
+        f.write_str(">")?;
+        let mut attribute = None;
+        for c in children.iter() {
+            f.write_str("\n")?;
+            fmt_element(f, c, depth + 1, attribute)?;
+            attribute = attribute_tag(c);
+        }
+        write!(f, "\n{:1$}", "", depth * 2)?;
+        return write!(f, "</{}>", name.unwrap_or("TTLV"));
+    }
+
+    write!(f, " type=\"{}\" value=\"", ttlv.value.type_().name())?;
+    let value_tag = value_tag(ttlv.tag, attribute);
+    match &ttlv.value {
+        Value::Structure(_) => unreachable!(),
+        Value::Integer(val) => write!(
+            f,
+            "{}",
+            MaskText {
+                tag: value_tag,
+                mask: *val,
+                separator: ' '
+            }
+        )?,
+        Value::LongInteger(val) => write!(f, "{}", val)?,
+        Value::BigInteger(val) => {
+            let sign = if is_negative(val) { "FF" } else { "00" };
+            for _ in val.len()..big_integer_len(val.len()) {
+                f.write_str(sign)?;
+            }
+            write!(f, "{}", Hex(val))?;
+        }
+        Value::Enumeration(val) => write!(f, "{}", EnumerationText(value_tag, *val))?,
+        Value::Boolean(val) => write!(f, "{}", val)?,
+        Value::TextString(val) => write_escaped(f, val)?,
+        Value::ByteString(val) => write!(f, "{}", Hex(val))?,
+        Value::DateTime(val) => write!(
+            f,
+            "{}",
+            DateTimeText {
+                seconds: *val,
+                micros: None
+            }
+        )?,
+        Value::Interval(val) => write!(f, "{}", val)?,
+        Value::DateTimeExtended(val) => write!(f, "{}", DateTimeText::from_micros(*val))?,
+    }
+    f.write_str("\"/>")
+}
+
+fn write_escaped(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '<' => f.write_str("&lt;")?,
+            '>' => f.write_str("&gt;")?,
+            '&' => f.write_str("&amp;")?,
+            '"' => f.write_str("&quot;")?,
+            '\'' => f.write_str("&apos;")?,
+            '\t' | '\n' | '\r' => write!(f, "&#{};", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+/// Encodes a TTLV tree in the KMIP XML encoding.
+pub fn to_string(ttlv: &Ttlv) -> String {
+    use alloc::string::ToString;
+    Xml(ttlv).to_string()
+}
+
+/// Decodes a TTLV tree from the KMIP XML encoding. Error offsets are byte positions in `xml`.
+pub fn from_str(xml: &str) -> Result<OwnedTtlv, Error> {
+    let mut parser = Parser { text: xml, pos: 0 };
+    parser.skip_misc()?;
+    let ttlv = parser.parse_element(0, None)?;
+    parser.skip_misc()?;
+    if parser.pos != xml.len() {
+        return Err(parser.error());
+    }
+    Ok(ttlv)
+}
+
+struct Parser<'s> {
+    text: &'s str,
+    pos: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn error(&self) -> Error {
+        Error::new(ErrorKind::Syntax).at(self.pos)
+    }
+    fn rest(&self) -> &'s str {
+        &self.text[self.pos..]
+    }
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+    fn skip_past(&mut self, end: &str) -> Result<(), Error> {
+        let len = self.rest().find(end).ok_or_else(|| self.error())?;
+        self.pos += len + end.len();
+        Ok(())
+    }
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// Skips whitespace, comments and processing instructions such as the XML declaration.
+    fn skip_misc(&mut self) -> Result<(), Error> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<&'s str, Error> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| {
+                !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' || c == ':')
+            })
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error());
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    /// Parses an element nested in `depth` structures, with `attribute` from a preceding Attribute Name.
+    fn parse_element(&mut self, depth: usize, attribute: Option<u32>) -> Result<OwnedTtlv, Error> {
+        let start = self.pos;
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::NestingTooDeep).at(start));
+        }
+        self.expect("<")?;
+        let name = self.parse_name()?;
+        let (mut tag, mut type_, mut value) = (None, None, None);
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with('>') || self.rest().starts_with("/>") {
+                break;
+            }
+            let attr = self.parse_name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let attr_value = self.parse_attribute_value()?;
+            match attr {
+                "tag" => tag = Some(attr_value),
+                "type" => type_ = Some(attr_value),
+                "value" => value = Some(attr_value),
+                _ => {}
+            }
+        }
+
+        let err = |kind| Error::new(kind).at(start);
+        let tag = match (name, tag) {
+            ("TTLV", Some(tag)) => parse_tag(&tag).map_err(|e| e.at(start))?,
+            (name, _) => Tag::from_name(name)
+                .map(|tag| tag.value())
+                .ok_or_else(|| err(ErrorKind::UnknownName))?,
+        };
+        let type_ = match type_ {
+            Some(type_) => {
+                Type::from_name(&type_).ok_or_else(|| err(ErrorKind::UnknownName).with_tag(tag))?
+            }
+            None => Type::Structure,
+        };
+
+        let value = if let Type::Structure = type_ {
+            let mut children = Vec::new();
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                let mut attribute = None;
+                loop {
+                    self.skip_misc()?;
+                    if self.rest().starts_with("</") {
+                        break;
+                    }
+                    let child = self
+                        .parse_element(depth + 1, attribute)
+                        .map_err(|e| e.within(tag, 0))?;
+                    attribute = attribute_tag(&child);
+                    children.push(child);
+                }
+                self.expect("</")?;
+                self.expect(name)?;
+                self.skip_whitespace();
+                self.expect(">")?;
+            } else {
+                self.expect("/>")?;
+            }
+            Value::Structure(children.into())
+        } else {
+            let value = value.ok_or_else(|| err(ErrorKind::Syntax).with_tag(tag))?;
+            let value_tag = value_tag(tag, attribute);
+            let value =
+                parse_primitive(value_tag, type_, &value).map_err(|e| e.at(start).with_tag(tag))?;
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                self.skip_misc()?;
+                self.expect("</")?;
+                self.expect(name)?;
+                self.skip_whitespace();
+                self.expect(">")?;
+            } else {
+                self.expect("/>")?;
+            }
+            value
+        };
+        Ok(Ttlv::new(tag, value))
+    }
+
+    fn parse_attribute_value(&mut self) -> Result<Cow<'s, str>, Error> {
+        let quote = match self.rest().chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.error()),
+        };
+        self.pos += 1;
+        let len = self.rest().find(quote).ok_or_else(|| self.error())?;
+        let raw = &self.rest()[..len];
+        let value = if raw.contains('&') {
+            Cow::Owned(unescape(raw).map_err(|e| e.at(self.pos))?)
+        } else {
+            Cow::Borrowed(raw)
+        };
+        self.pos += len + 1;
+        Ok(value)
+    }
+}
+
+fn unescape(raw: &str) -> Result<String, Error> {
+    let mut s = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(amp) = rest.find('&') {
+        s.push_str(&rest[..amp]);
+        let semi = rest[amp..]
+            .find(';')
+            .ok_or_else(|| Error::new(ErrorKind::Syntax))?;
+        let c = match &rest[amp + 1..amp + semi] {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32)
+                .ok_or_else(|| Error::new(ErrorKind::Syntax))?,
+        };
+        s.push(c);
+        rest = &rest[amp + semi + 1..];
+    }
+    s.push_str(rest);
+    Ok(s)
+}
+
+/// Parses a decimal number, or `0x`-prefixed hex holding the value's bits.
+fn number<T: FromStr>(text: &str, from_bits: fn(u64) -> Option<T>) -> Result<T, Error> {
+    if text.starts_with("0x") {
+        from_bits(parse_hex_int(text)?)
+    } else {
+        text.parse().ok()
+    }
+    .ok_or_else(|| Error::new(ErrorKind::Syntax))
+}
+
+fn parse_primitive(tag: u32, type_: Type, text: &str) -> Result<Value<'static>, Error> {
+    let i32_bits = |bits| u32::try_from(bits).ok().map(|bits| bits as i32);
+    let u32_bits = |bits| u32::try_from(bits).ok();
+    let i64_bits = |bits| Some(bits as i64);
+    let hex = |text: &str| parse_hex_bytes(text.strip_prefix("0x").unwrap_or(text));
+    Ok(match type_ {
+        Type::Structure => unreachable!(),
+        Type::Integer if text.starts_with(|c: char| c.is_ascii_digit() || c == '-') => {
+            Value::Integer(number(text, i32_bits)?)
+        }
+        Type::Integer => Value::Integer(parse_mask(tag, text)?),
+        Type::LongInteger => Value::LongInteger(number(text, i64_bits)?),
+        Type::BigInteger => Value::BigInteger(hex(text)?.into()),
+        Type::Enumeration => Value::Enumeration(parse_enumeration(tag, text)?),
+        Type::Boolean => match text {
+            "true" | "0x0000000000000001" => Value::Boolean(true),
+            "false" | "0x0000000000000000" => Value::Boolean(false),
+            _ => return Err(Error::new(ErrorKind::InvalidBoolean)),
+        },
+        Type::TextString => Value::TextString(String::from(text).into()),
+        Type::ByteString => Value::ByteString(hex(text)?.into()),
+        Type::DateTime if text.starts_with("0x") => Value::DateTime(number(text, i64_bits)?),
+        Type::DateTime => Value::DateTime(DateTimeText::parse(text)?.seconds),
+        Type::Interval => Value::Interval(number(text, u32_bits)?),
+        Type::DateTimeExtended if text.starts_with("0x") => {
+            Value::DateTimeExtended(number(text, i64_bits)?)
+        }
+        Type::DateTimeExtended => Value::DateTimeExtended(DateTimeText::parse(text)?.to_micros()),
+    })
+}
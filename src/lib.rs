@@ -1,26 +1,65 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 #![no_std]
+#[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+pub mod kmip;
+
+#[cfg(feature = "alloc")]
+mod convert;
+#[cfg(feature = "alloc")]
+mod decoder;
+mod display;
+mod error;
+mod events;
+#[cfg(feature = "std")]
+mod reader;
 mod ttlv;
 mod util;
+mod view;
 
+#[cfg(feature = "alloc")]
+pub use crate::convert::{FromTtlv, ToChildren, ToTtlv};
+#[cfg(feature = "alloc")]
+pub use crate::decoder::TtlvDecoder;
+pub use crate::display::{hex_dump, hex_dump_with, HexDump, Pretty, TagNames};
+pub use crate::error::{Error, ErrorKind};
+pub use crate::events::{Event, Events};
+#[cfg(feature = "std")]
+pub use crate::reader::TtlvReader;
 pub use crate::ttlv::*;
-pub use crate::util::parse_ttlv_len;
+pub use crate::util::{parse_ttlv_len, DateTimeMicros, TwosComplement, DEFAULT_MAX_MESSAGE_LEN};
+pub use crate::view::{Children, TtlvRef};
+#[cfg(feature = "derive")]
+pub use ttlv_derive::{FromTtlv, ToTtlv};
 
-#[cfg(test)]
+#[cfg(all(test, feature = "alloc"))]
+#[allow(non_local_definitions)] // num-derive 0.3 expands its impls inside a const block
 mod tests {
     use super::{Value::*, *};
-    use alloc::vec;
+    use alloc::{string::ToString, vec};
     use num_derive::{FromPrimitive, ToPrimitive};
 
     #[derive(Copy, Clone, PartialEq, Debug, FromPrimitive, ToPrimitive)]
     pub enum Tag {
-        Request,
-        RequestHeader,
-        ProtocolVersion,
-        RequestBody,
+        Request = 0x420078,
+        RequestHeader = 0x420077,
+        ProtocolVersion = 0x420069,
+        RequestBody = 0x420079,
+        VendorExtension = 0x540001,
+    }
+
+    /// Encodes `depth` Request structures, each holding the next and the innermost empty.
+    fn nested(depth: usize) -> vec::Vec<u8> {
+        let mut encoded = vec![];
+        for i in 1..=depth {
+            encoded.extend_from_slice(&[0x42, 0x00, 0x78, 0x01]);
+            encoded.extend_from_slice(&(((depth - i) * 8) as u32).to_be_bytes());
+        }
+        encoded
     }
 
     #[test]
@@ -28,18 +67,22 @@ mod tests {
         // Construct TTLV message
         let message: Ttlv = Ttlv::new(
             Tag::Request,
-            Structure(vec![
-                Ttlv::new(
-                    Tag::RequestHeader,
-                    Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))]),
-                ),
-                Ttlv::new(Tag::RequestBody, TextString("message body")),
-            ]),
+            Structure(
+                vec![
+                    Ttlv::new(
+                        Tag::RequestHeader,
+                        Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+                    ),
+                    Ttlv::new(Tag::RequestBody, TextString("message body".into())),
+                ]
+                .into(),
+            ),
         );
 
         // Encode TTLV message
         let encoded = &mut [0u8; 1000];
         let encoded_len = message.encode(encoded)?;
+        assert_eq!(message.encoded_len(), encoded_len);
 
         // Decode TTLV message
         let (decoded, decoded_len) = Ttlv::decode(encoded)?;
@@ -55,4 +98,881 @@ mod tests {
         assert_eq!("message body", message_body);
         Ok(())
     }
+
+    #[test]
+    fn big_integer() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::RequestBody,
+            BigInteger((&[0x80, 0x00, 0x01][..]).into()),
+        );
+
+        // Big Integers are sign-extended to a multiple of 8 bytes
+        let encoded = &mut [0u8; 16];
+        assert_eq!(16, message.encode(encoded)?);
+        assert_eq!(16, message.encoded_len());
+        assert_eq!(&[0, 0, 0, 8], &encoded[4..8]);
+        assert_eq!(
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x80, 0x00, 0x01],
+            &encoded[8..]
+        );
+
+        let (decoded, _) = Ttlv::decode(encoded)?;
+        let TwosComplement(bytes) = decoded.value()?;
+        assert_eq!(&[0x80, 0x00, 0x01], bytes);
+        assert_eq!(-0x7F_FFFF, decoded.value::<i128>()?);
+
+        // Lengths that are not a multiple of 8 are rejected
+        encoded[7] = 7;
+        assert!(matches!(Ttlv::decode(encoded), Err(e) if e.kind() == ErrorKind::InvalidLength));
+
+        // Zero with no significant bytes is still written as eight bytes
+        let zero = Ttlv::new(Tag::RequestBody, TwosComplement(&[]).into());
+        assert_eq!(16, zero.encoded_len());
+        assert_eq!(16, zero.encode(encoded)?);
+        assert_eq!(&[0, 0, 0, 8], &encoded[4..8]);
+        assert_eq!(&[0; 8], &encoded[8..]);
+        assert_eq!(0, Ttlv::decode(encoded)?.0.value::<i128>()?);
+        Ok(())
+    }
+
+    #[test]
+    fn date_time_extended() -> Result<(), Error> {
+        let message = Ttlv::new(Tag::RequestBody, DateTimeExtended(1_570_000_000_123_456));
+
+        let encoded = &mut [0u8; 16];
+        message.encode(encoded)?;
+        assert_eq!(0x0B, encoded[3]);
+
+        let (decoded, _) = Ttlv::decode(encoded)?;
+        assert_eq!(message, decoded);
+        let DateTimeMicros(micros) = decoded.value()?;
+        assert_eq!(1_570_000_000_123_456, micros);
+        Ok(())
+    }
+
+    #[test]
+    fn extension_tag() -> Result<(), Error> {
+        let message = Ttlv::new(Tag::VendorExtension, Integer(1));
+
+        let encoded = &mut [0u8; 16];
+        message.encode(encoded)?;
+        assert_eq!(&[0x54, 0x00, 0x01], &encoded[..3]);
+
+        let (decoded, _) = Ttlv::decode(encoded)?;
+        assert_eq!(Tag::VendorExtension, decoded.tag());
+        assert!(matches!(
+            Ttlv::new(0x0100_0000, Integer(1)).encode(encoded),
+            Err(e) if e.kind() == ErrorKind::InvalidTag
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn decode_strict() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(Tag::ProtocolVersion, Integer(6)),
+                    Ttlv::new(Tag::RequestBody, TextString("message body".into())),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = &mut [0u8; 48];
+        message.encode(encoded)?;
+        assert_eq!(message, Ttlv::decode_strict(encoded)?.0);
+
+        // Corrupt the type of the second child
+        encoded[27] = 0xFF;
+        let (truncated, _) = Ttlv::decode(encoded)?;
+        assert_eq!(1, truncated.child_iter()?.count());
+        assert!(matches!(
+            Ttlv::decode_strict(encoded),
+            Err(e) if e.kind() == ErrorKind::UnsupportedType
+        ));
+
+        // Claim a structure length that leaves a partial child behind
+        encoded[27] = 0x07;
+        encoded[7] = 36;
+        assert!(matches!(
+            Ttlv::decode_strict(encoded),
+            Err(e) if e.kind() == ErrorKind::InsufficientBufferSize
+        ));
+
+        // Structures nested deeper than MAX_DEPTH are rejected rather than recursed into
+        let nested = nested(MAX_DEPTH + 1);
+        let err = Ttlv::decode_strict(&nested).unwrap_err();
+        assert_eq!(ErrorKind::NestingTooDeep, err.kind());
+        assert_eq!(Some(MAX_DEPTH * 8), err.offset());
+        assert!(Ttlv::decode_strict(&nested[8..]).is_ok());
+        let mut decoded = &Ttlv::decode(&nested)?.0;
+        for _ in 0..MAX_DEPTH - 1 {
+            decoded = decoded.child_iter()?.next().unwrap();
+        }
+        assert_eq!(0, decoded.child_iter()?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn decode_validation() -> Result<(), Error> {
+        let encoded = &mut [0u8; 16];
+        Ttlv::new(Tag::ProtocolVersion, Integer(6)).encode(encoded)?;
+        Ttlv::decode_strict(encoded)?;
+
+        encoded[15] = 1;
+        assert!(
+            matches!(Ttlv::decode_strict(encoded), Err(e) if e.kind() == ErrorKind::NonZeroPadding)
+        );
+        assert_eq!(Integer(6), Ttlv::decode(encoded)?.0.value);
+        encoded[15] = 0;
+        encoded[7] = 8;
+        assert!(
+            matches!(Ttlv::decode_strict(encoded), Err(e) if e.kind() == ErrorKind::InvalidLength)
+        );
+        assert_eq!(Integer(6), Ttlv::decode(encoded)?.0.value);
+        encoded[7] = 0;
+        assert!(matches!(Ttlv::decode(encoded), Err(e) if e.kind() == ErrorKind::InvalidLength));
+
+        Ttlv::new(Tag::ProtocolVersion, Boolean(true)).encode(encoded)?;
+        encoded[15] = 2;
+        assert!(
+            matches!(Ttlv::decode_strict(encoded), Err(e) if e.kind() == ErrorKind::InvalidBoolean)
+        );
+        assert_eq!(Boolean(true), Ttlv::decode(encoded)?.0.value);
+
+        // A non-conformant child doesn't cost its siblings in a lenient decode
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(Tag::ProtocolVersion, Integer(6)),
+                    Ttlv::new(Tag::RequestBody, Integer(7)),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = &mut message.encode_to_vec()?;
+        encoded[23] = 1;
+        assert_eq!(message, Ttlv::decode(encoded)?.0);
+        assert!(
+            matches!(Ttlv::decode_strict(encoded), Err(e) if e.kind() == ErrorKind::NonZeroPadding)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn error_context() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![Ttlv::new(
+                    Tag::RequestHeader,
+                    Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+                )]
+                .into(),
+            ),
+        );
+        let encoded = &mut [0u8; 32];
+        message.encode(encoded)?;
+
+        let err = message
+            .path(&[Tag::RequestHeader, Tag::ProtocolVersion])?
+            .value::<&str>()
+            .unwrap_err();
+        assert_eq!(Some(Type::TextString), err.expected_type());
+        assert_eq!(Some(Type::Integer), err.actual_type());
+        let err = message
+            .path(&[Tag::RequestHeader, Tag::RequestBody])
+            .unwrap_err();
+        assert_eq!(ErrorKind::ChildNotFound, err.kind());
+        assert_eq!(&[0x420078, 0x420077], err.path());
+
+        encoded[31] = 1;
+        let err = Ttlv::decode_strict(encoded).unwrap_err();
+        assert_eq!(ErrorKind::NonZeroPadding, err.kind());
+        assert_eq!(Some(16), err.offset());
+        assert_eq!(Some(0x420069), err.tag());
+        assert_eq!(
+            "non-zero padding for tag 0x420069 at offset 16 in 0x420078/0x420077",
+            err.to_string()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn encode_to_vec() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(Tag::RequestHeader, Structure(vec![].into())),
+                    Ttlv::new(Tag::RequestBody, TextString("".into())),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = message.encode_to_vec()?;
+        assert_eq!(message.encoded_len(), encoded.len());
+        assert_eq!(message, Ttlv::decode_strict(&encoded)?.0);
+
+        #[cfg(feature = "std")]
+        {
+            let mut written = std::vec::Vec::new();
+            assert_eq!(
+                encoded.len(),
+                message.encode_to_writer(&mut written).unwrap()
+            );
+            assert_eq!(encoded, written);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn into_owned() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![Ttlv::new(
+                    Tag::RequestBody,
+                    TextString("message body".into()),
+                )]
+                .into(),
+            ),
+        );
+        let owned: OwnedTtlv = {
+            let encoded = message.encode_to_vec()?;
+            Ttlv::decode(&encoded)?.0.into_owned()
+        };
+        assert_eq!(message, owned);
+        let message_body: &str = owned.path(&[Tag::RequestBody])?.value()?;
+        assert_eq!("message body", message_body);
+
+        // Borrowed children compare and encode like owned ones, and can be made owned too
+        let children = [Ttlv::new(
+            Tag::RequestBody,
+            TextString("message body".into()),
+        )];
+        let borrowed = Ttlv::new(Tag::Request, Structure(Items::Borrowed(&children)));
+        assert_eq!(message, borrowed);
+        assert_eq!(message.encode_to_vec()?, borrowed.encode_to_vec()?);
+        assert_eq!(message, borrowed.into_owned());
+        Ok(())
+    }
+
+    #[test]
+    fn kmip_tags() -> Result<(), Error> {
+        use crate::kmip::Tag as KmipTag;
+
+        assert_eq!(0x420069, KmipTag::ProtocolVersion.value());
+        assert_eq!(
+            Some(KmipTag::RequestMessage),
+            KmipTag::from_name("RequestMessage")
+        );
+        assert_eq!(Some("RequestHeader"), KmipTag::from_value(0x420077).name());
+        assert_eq!(KmipTag::Unknown(0x540001), KmipTag::from_value(0x540001));
+
+        let message = Ttlv::new(
+            KmipTag::RequestMessage,
+            Structure(vec![Ttlv::new(Tag::VendorExtension, Integer(1))].into()),
+        );
+        let encoded = message.encode_to_vec()?;
+        let (decoded, _) = Ttlv::decode(&encoded)?;
+        let child = decoded.path(&[KmipTag::Unknown(0x540001)])?;
+        assert_eq!(KmipTag::Unknown(0x540001), child.tag());
+        Ok(())
+    }
+
+    #[test]
+    fn kmip_enumerations() -> Result<(), Error> {
+        use crate::kmip::{self, Operation, ResultStatus};
+
+        let message = Ttlv::new(
+            kmip::Tag::BatchItem,
+            Structure(
+                vec![
+                    Ttlv::new(kmip::Tag::Operation, Operation::Locate.into()),
+                    Ttlv::new(kmip::Tag::ResultStatus, Enumeration(0x8000_0001)),
+                ]
+                .into(),
+            ),
+        );
+        let operation: Operation = message.path(&[kmip::Tag::Operation])?.value()?;
+        assert_eq!(Operation::Locate, operation);
+        let status: ResultStatus = message.path(&[kmip::Tag::ResultStatus])?.value()?;
+        assert_eq!(ResultStatus::Unknown(0x8000_0001), status);
+
+        assert_eq!(
+            Some("Locate"),
+            kmip::enumeration_name(kmip::Tag::Operation, 0x08)
+        );
+        assert_eq!(
+            Some(0x08),
+            kmip::enumeration_value(kmip::Tag::Operation, "Locate")
+        );
+
+        // Names follow the normalization of the KMIP text encodings
+        assert_eq!(
+            Some(kmip::HashingAlgorithm::SHA_256),
+            kmip::HashingAlgorithm::from_name("SHA_256")
+        );
+        assert_eq!(
+            Some(0x03),
+            kmip::enumeration_value(kmip::Tag::KeyFormatType, "PKCS_1")
+        );
+        assert_eq!(
+            Some("DES3"),
+            kmip::enumeration_name(kmip::Tag::CryptographicAlgorithm, 0x02)
+        );
+        assert_eq!(Some("X_509CertificateIdentifier"), kmip::tag_name(0x4200B5));
+        Ok(())
+    }
+
+    #[test]
+    fn pretty() {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(
+                        Tag::RequestHeader,
+                        Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+                    ),
+                    Ttlv::new(Tag::RequestBody, ByteString((&[0xCA, 0xFE][..]).into())),
+                ]
+                .into(),
+            ),
+        );
+        assert_eq!(
+            "0x420078 Structure\n  0x420077 Structure\n    0x420069 Integer: 6\n  0x420079 ByteString: CAFE",
+            message.to_string()
+        );
+        assert_eq!(
+            "RequestMessage (0x420078) Structure\n  \
+             RequestHeader (0x420077) Structure\n    \
+             ProtocolVersion (0x420069) Integer: 6\n  \
+             RequestPayload (0x420079) ByteString: CAFE",
+            message.pretty_with(kmip::tag_name).to_string()
+        );
+    }
+
+    #[test]
+    fn kmip_json() -> Result<(), Error> {
+        use crate::kmip::{self, json, Operation};
+
+        let message = Ttlv::new(
+            kmip::Tag::BatchItem,
+            Structure(
+                vec![
+                    Ttlv::new(kmip::Tag::Operation, Operation::Locate.into()),
+                    Ttlv::new(kmip::Tag::MaximumItems, Integer(-1)),
+                    Ttlv::new(kmip::Tag::Modulus, BigInteger((&[0xFF, 0x01][..]).into())),
+                    Ttlv::new(kmip::Tag::Salt, ByteString((&[0xCA, 0xFE][..]).into())),
+                    Ttlv::new(kmip::Tag::Name, TextString("a \"b\"\n".into())),
+                    Ttlv::new(kmip::Tag::ActivationDate, DateTime(1_000_000_000)),
+                    Ttlv::new(0x540001, DateTimeExtended(1_000_000_000_000_001)),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = json::to_string(&message);
+        assert_eq!(
+            "{\"tag\":\"BatchItem\",\"type\":\"Structure\",\"value\":[\
+             {\"tag\":\"Operation\",\"type\":\"Enumeration\",\"value\":\"Locate\"},\
+             {\"tag\":\"MaximumItems\",\"type\":\"Integer\",\"value\":-1},\
+             {\"tag\":\"Modulus\",\"type\":\"BigInteger\",\"value\":\"0xFFFFFFFFFFFFFF01\"},\
+             {\"tag\":\"Salt\",\"type\":\"ByteString\",\"value\":\"CAFE\"},\
+             {\"tag\":\"Name\",\"type\":\"TextString\",\"value\":\"a \\\"b\\\"\\n\"},\
+             {\"tag\":\"ActivationDate\",\"type\":\"DateTime\",\"value\":\"2001-09-09T01:46:40+00:00\"},\
+             {\"tag\":\"0x540001\",\"type\":\"DateTimeExtended\",\"value\":\"2001-09-09T01:46:40.000001+00:00\"}]}",
+            encoded
+        );
+        let decoded = json::from_str(&encoded)?;
+        let modulus: TwosComplement = decoded.path(&[kmip::Tag::Modulus])?.value()?;
+        assert_eq!(&[0xFF, 0x01], modulus.0);
+        assert_eq!(
+            message.path(&[kmip::Tag::Name])?,
+            decoded.path(&[kmip::Tag::Name])?
+        );
+        assert_eq!(
+            DateTime(1_000_000_000),
+            json::from_str(
+                r#"{"tag": "ActivationDate", "type": "DateTime", "value": "2001-09-09T03:46:40+02:00"}"#
+            )?
+            .value
+        );
+
+        // Long Integers beyond the range JSON numbers carry exactly are written as hex, including both extremes
+        for val in [i64::MIN, i64::MAX] {
+            let long = Ttlv::new(kmip::Tag::MaximumItems, LongInteger(val));
+            let encoded = json::to_string(&long);
+            assert!(encoded.contains("\"value\":\"0x"));
+            assert_eq!(long, json::from_str(&encoded)?);
+        }
+
+        // Date Times with years outside 0000-9999 are written as hex
+        for value in [
+            DateTime(i64::MAX),
+            DateTime(-100_000_000_000),
+            DateTimeExtended(i64::MIN),
+        ] {
+            let date = Ttlv::new(kmip::Tag::ActivationDate, value);
+            let encoded = json::to_string(&date);
+            assert!(encoded.contains("\"value\":\"0x"));
+            assert_eq!(date, json::from_str(&encoded)?);
+            assert_eq!(date, kmip::xml::from_str(&kmip::xml::to_string(&date))?);
+        }
+
+        let err = json::from_str(&"[".repeat(200_000)).unwrap_err();
+        assert_eq!(ErrorKind::NestingTooDeep, err.kind());
+
+        let err = json::from_str(r#"{"tag":"BatchItem","value":[{"tag":"Operation","type":"Enumeration","value":"Bogus"}]}"#)
+            .unwrap_err();
+        assert_eq!(ErrorKind::UnknownName, err.kind());
+        assert_eq!(Some(28), err.offset());
+        assert_eq!(&[0x42000F], err.path());
+
+        // Hex tags and enumerations that don't fit are rejected rather than truncated
+        for (json, kind) in [
+            (
+                r#"{"tag":"0x100420069","type":"Integer","value":1}"#,
+                ErrorKind::InvalidTag,
+            ),
+            (
+                r#"{"tag":"0x1000000","type":"Integer","value":1}"#,
+                ErrorKind::InvalidTag,
+            ),
+            (
+                r#"{"tag":"Operation","type":"Enumeration","value":"0x100000008"}"#,
+                ErrorKind::Syntax,
+            ),
+        ] {
+            assert_eq!(kind, json::from_str(json).unwrap_err().kind());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn kmip_xml() -> Result<(), Error> {
+        use crate::kmip::{self, xml, Operation};
+
+        let message = Ttlv::new(
+            kmip::Tag::BatchItem,
+            Structure(
+                vec![
+                    Ttlv::new(kmip::Tag::Operation, Operation::Locate.into()),
+                    Ttlv::new(kmip::Tag::Modulus, BigInteger((&[0xFF, 0x01][..]).into())),
+                    Ttlv::new(kmip::Tag::Name, TextString("<a & 'b'>".into())),
+                    Ttlv::new(kmip::Tag::ActivationDate, DateTime(1_000_000_000)),
+                    Ttlv::new(
+                        0x540001,
+                        Structure(vec![Ttlv::new(0x540002, Boolean(true))].into()),
+                    ),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = xml::to_string(&message);
+        assert_eq!(
+            "<BatchItem>\n  \
+             <Operation type=\"Enumeration\" value=\"Locate\"/>\n  \
+             <Modulus type=\"BigInteger\" value=\"FFFFFFFFFFFFFF01\"/>\n  \
+             <Name type=\"TextString\" value=\"&lt;a &amp; &apos;b&apos;&gt;\"/>\n  \
+             <ActivationDate type=\"DateTime\" value=\"2001-09-09T01:46:40+00:00\"/>\n  \
+             <TTLV tag=\"0x540001\">\n    \
+             <TTLV tag=\"0x540002\" type=\"Boolean\" value=\"true\"/>\n  \
+             </TTLV>\n\
+             </BatchItem>",
+            encoded
+        );
+        assert_eq!(encoded, xml::to_string(&xml::from_str(&encoded)?));
+        assert_eq!(
+            Integer(6),
+            xml::from_str(
+                "<?xml version=\"1.0\"?>\n<!-- vector -->\n<ProtocolVersionMajor type='Integer' value='0x00000006'></ProtocolVersionMajor>"
+            )?
+            .value
+        );
+
+        let err = xml::from_str(
+            "<BatchItem>\n  <Operation type=\"Enumeration\" value=\"Bogus\"/>\n</BatchItem>",
+        )
+        .unwrap_err();
+        assert_eq!(ErrorKind::UnknownName, err.kind());
+        assert_eq!(Some(14), err.offset());
+        assert_eq!(&[0x42000F], err.path());
+
+        // A Create request from the KMIP 1.4 test cases
+        let vector = r#"
+<RequestMessage>
+  <RequestHeader>
+    <ProtocolVersion>
+      <ProtocolVersionMajor type="Integer" value="1"/>
+      <ProtocolVersionMinor type="Integer" value="4"/>
+    </ProtocolVersion>
+    <BatchCount type="Integer" value="1"/>
+  </RequestHeader>
+  <BatchItem>
+    <Operation type="Enumeration" value="Create"/>
+    <RequestPayload>
+      <ObjectType type="Enumeration" value="SymmetricKey"/>
+      <TemplateAttribute>
+        <Attribute>
+          <AttributeName type="TextString" value="Cryptographic Algorithm"/>
+          <AttributeValue type="Enumeration" value="AES"/>
+        </Attribute>
+        <Attribute>
+          <AttributeName type="TextString" value="Cryptographic Length"/>
+          <AttributeValue type="Integer" value="128"/>
+        </Attribute>
+        <Attribute>
+          <AttributeName type="TextString" value="Cryptographic Usage Mask"/>
+          <AttributeValue type="Integer" value="Decrypt Encrypt"/>
+        </Attribute>
+      </TemplateAttribute>
+    </RequestPayload>
+  </BatchItem>
+</RequestMessage>
+"#;
+        let request = xml::from_str(vector)?;
+        let attributes = match &request.value {
+            Structure(items) => match &items[1].value {
+                Structure(items) => match &items[1].value {
+                    Structure(items) => &items[1],
+                    _ => unreachable!(),
+                },
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let values: vec::Vec<_> = match &attributes.value {
+            Structure(attributes) => attributes
+                .iter()
+                .map(|attribute| match &attribute.value {
+                    Structure(items) => items[1].value.clone(),
+                    _ => unreachable!(),
+                })
+                .collect(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            vec![
+                kmip::CryptographicAlgorithm::AES.into(),
+                Integer(128),
+                Integer(0x0000000C)
+            ],
+            values
+        );
+        let encoded = xml::to_string(&request);
+        assert!(encoded.contains("<AttributeValue type=\"Integer\" value=\"Encrypt Decrypt\"/>"));
+        assert_eq!(request, xml::from_str(&encoded)?);
+
+        // JSON names the same Attribute Values
+        let json = kmip::json::to_string(&request);
+        assert!(json.contains(r#"{"tag":"AttributeValue","type":"Enumeration","value":"AES"}"#));
+        assert!(
+            json.contains(r#"{"tag":"AttributeValue","type":"Integer","value":"Encrypt|Decrypt"}"#)
+        );
+        assert_eq!(request, kmip::json::from_str(&json)?);
+
+        let err = xml::from_str(&"<RequestMessage>".repeat(200_000)).unwrap_err();
+        assert_eq!(ErrorKind::NestingTooDeep, err.kind());
+
+        let err =
+            xml::from_str(r#"<TTLV tag="0x100420069" type="Integer" value="1"/>"#).unwrap_err();
+        assert_eq!(ErrorKind::InvalidTag, err.kind());
+        Ok(())
+    }
+
+    #[test]
+    fn hex_dump() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(Tag::ProtocolVersion, Integer(6)),
+                    Ttlv::new(Tag::RequestBody, TextString("message body".into())),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = &mut message.encode_to_vec()?;
+        assert_eq!(
+            "0000  42 00 78 | 01 | 00 00 00 28  RequestMessage (0x420078) Structure, length 40\n\
+             0008    42 00 69 | 02 | 00 00 00 04  ProtocolVersion (0x420069) Integer, length 4\n\
+             0010      00 00 00 06 00 00 00 00      6, padding 4\n\
+             0018    42 00 79 | 07 | 00 00 00 0C  RequestPayload (0x420079) TextString, length 12\n\
+             0020      6D 65 73 73 61 67 65 20      \"message body\"\n\
+             0028      62 6F 64 79 00 00 00 00      padding 4",
+            super::hex_dump_with(encoded, kmip::tag_name).to_string()
+        );
+
+        // Corrupt the type of the second child
+        encoded[27] = 0xFF;
+        assert_eq!(
+            "0000  42 00 78 | 01 | 00 00 00 28  0x420078 Structure, length 40\n\
+             0008    42 00 69 | 02 | 00 00 00 04  0x420069 Integer, length 4\n\
+             0010      00 00 00 06 00 00 00 00      6, padding 4\n\
+             0018    42 00 79 | FF | 00 00 00 0C  0x420079 unknown type 0xFF, length 12\n\
+             0020      error: unsupported type, parsing stopped\n\
+             0020      6D 65 73 73 61 67 65 20\n\
+             0028      62 6F 64 79 00 00 00 00",
+            super::hex_dump(encoded).to_string()
+        );
+
+        // A zero-length Integer has no value rows but still reports why it can't be decoded
+        assert_eq!(
+            "0000  42 00 69 | 02 | 00 00 00 00  0x420069 Integer, length 0\n\
+             0008                                 error: invalid length for type",
+            super::hex_dump(&[0x42, 0x00, 0x69, 0x02, 0x00, 0x00, 0x00, 0x00]).to_string()
+        );
+
+        // Nesting deeper than MAX_DEPTH stops with an error line
+        let nested = nested(MAX_DEPTH + 1);
+        let dump = super::hex_dump(&nested).to_string();
+        assert_eq!(MAX_DEPTH + 2, dump.lines().count());
+        assert!(dump
+            .lines()
+            .nth(MAX_DEPTH + 1)
+            .unwrap()
+            .ends_with("error: structures nested too deeply, parsing stopped"));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn reader() -> Result<(), Error> {
+        let first = Ttlv::new(
+            Tag::Request,
+            Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+        );
+        let second = Ttlv::new(Tag::RequestBody, TextString("message body".into()));
+        let mut stream = first.encode_to_vec()?;
+        stream.extend(second.encode_to_vec()?);
+
+        let mut reader = TtlvReader::new(&stream[..]);
+        assert_eq!(Some(first), reader.read().unwrap());
+        assert_eq!(Some(second), reader.read().unwrap());
+        assert_eq!(None, reader.read().unwrap());
+
+        let mut reader = TtlvReader::new(&stream[..]).with_max_len(16);
+        let err = reader.read().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            "message exceeds maximum length for tag 0x420078 at offset 0",
+            err.to_string()
+        );
+
+        // Lengths near u32::MAX are rejected without reading further
+        let header = [0x42, 0x00, 0x78, 0x01, 0xFF, 0xFF, 0xFF, 0xFF];
+        let mut reader = TtlvReader::new(&header[..]);
+        assert_eq!(
+            std::io::ErrorKind::InvalidData,
+            reader.read().unwrap_err().kind()
+        );
+
+        let mut reader = TtlvReader::new(&stream[..20]);
+        let err = reader.read().unwrap_err();
+        assert_eq!(std::io::ErrorKind::UnexpectedEof, err.kind());
+
+        // A megabyte of nested structure headers is rejected without exhausting the stack
+        let nested = nested(1 << 17);
+        let err = TtlvReader::new(&nested[..]).read().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert!(err.to_string().starts_with("structures nested too deeply"));
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_decoder() -> Result<(), Error> {
+        let first = Ttlv::new(
+            Tag::Request,
+            Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+        );
+        let second = Ttlv::new(Tag::RequestBody, TextString("message body".into()));
+        let mut stream = first.encode_to_vec()?;
+        stream.extend(second.encode_to_vec()?);
+
+        let mut decoder = TtlvDecoder::new();
+        assert_eq!(8, decoder.needed());
+        let mut input = &stream[..5];
+        assert_eq!(None, decoder.feed(&mut input)?);
+        assert_eq!(3, decoder.needed());
+
+        // Once the header is in, the rest of the message is known exactly
+        let mut input = &stream[5..10];
+        assert_eq!(None, decoder.feed(&mut input)?);
+        assert_eq!(14, decoder.needed());
+
+        let mut input = &stream[10..];
+        assert_eq!(Some(first), decoder.feed(&mut input)?);
+        assert_eq!(24, input.len());
+        assert_eq!(Some(second), decoder.feed(&mut input)?);
+        assert!(input.is_empty());
+        assert_eq!(8, decoder.needed());
+
+        let mut decoder = TtlvDecoder::new().with_max_len(16);
+        let mut input = &stream[..];
+        let err = decoder.feed(&mut input).unwrap_err();
+        assert_eq!(ErrorKind::MessageTooLarge, err.kind());
+        assert_eq!(8, decoder.needed());
+
+        let mut corrupt = stream.clone();
+        corrupt[3] = 0xFF;
+        let err = TtlvDecoder::new().feed(&mut &corrupt[..]).unwrap_err();
+        assert_eq!(ErrorKind::UnsupportedType, err.kind());
+        Ok(())
+    }
+
+    #[test]
+    fn borrowed_view() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(
+                        Tag::RequestHeader,
+                        Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+                    ),
+                    Ttlv::new(Tag::RequestBody, TextString("message body".into())),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = &mut message.encode_to_vec()?;
+
+        let (view, view_len) = TtlvRef::decode(encoded)?;
+        assert_eq!(encoded.len(), view_len);
+        assert_eq!(Tag::Request, view.tag());
+        assert_eq!(2, view.children()?.count());
+        let version: i32 = view
+            .path(&[Tag::RequestHeader, Tag::ProtocolVersion])?
+            .value()?;
+        assert_eq!(6, version);
+        let body = view.path(&[Tag::RequestBody])?;
+        assert_eq!("message body", body.value::<&str>()?);
+        assert_eq!(
+            Some(Type::Structure),
+            view.value::<i32>().unwrap_err().actual_type()
+        );
+        assert_eq!(
+            ErrorKind::ChildNotFound,
+            view.path::<u32>(&[]).unwrap_err().kind()
+        );
+
+        // Strings and bytes borrow from the buffer, so they outlive the views they were reached through
+        fn request_body(buf: &[u8]) -> Result<&str, Error> {
+            TtlvRef::decode(buf)?.0.path(&[Tag::RequestBody])?.value()
+        }
+        assert_eq!("message body", request_body(encoded)?);
+
+        // Children are only validated when reached
+        encoded[35] = 0xFF;
+        let (view, _) = TtlvRef::decode(encoded)?;
+        assert!(view
+            .path(&[Tag::RequestHeader])?
+            .children()?
+            .all(|c| c.is_ok()));
+        let err = view.path(&[Tag::RequestBody]).unwrap_err();
+        assert_eq!(ErrorKind::UnsupportedType, err.kind());
+        assert_eq!(Some(32), err.offset());
+        assert_eq!(&[0x420078], err.path());
+
+        // Offsets below the first level count from the start of the message
+        encoded[19] = 0xFF;
+        let (view, _) = TtlvRef::decode(encoded)?;
+        let err = view
+            .path(&[Tag::RequestHeader, Tag::ProtocolVersion])
+            .unwrap_err();
+        assert_eq!(ErrorKind::UnsupportedType, err.kind());
+        assert_eq!(Some(16), err.offset());
+        assert_eq!(&[0x420078, 0x420077], err.path());
+        Ok(())
+    }
+
+    #[test]
+    fn events() -> Result<(), Error> {
+        let message = Ttlv::new(
+            Tag::Request,
+            Structure(
+                vec![
+                    Ttlv::new(
+                        Tag::RequestHeader,
+                        Structure(vec![Ttlv::new(Tag::ProtocolVersion, Integer(6))].into()),
+                    ),
+                    Ttlv::new(Tag::RequestBody, Structure(vec![].into())),
+                ]
+                .into(),
+            ),
+        );
+        let encoded = &mut message.encode_to_vec()?;
+
+        let mut events = Events::new(encoded);
+        let expected = [
+            Event::StartStructure {
+                tag: 0x420078,
+                len: 32,
+            },
+            Event::StartStructure {
+                tag: 0x420077,
+                len: 16,
+            },
+            Event::Primitive {
+                tag: 0x420069,
+                value: Integer(6),
+            },
+            Event::EndStructure,
+            Event::StartStructure {
+                tag: 0x420079,
+                len: 0,
+            },
+            Event::EndStructure,
+            Event::EndStructure,
+        ];
+        for event in &expected {
+            assert_eq!(event, &events.next().unwrap()?);
+        }
+        assert_eq!(None, events.next());
+        assert_eq!(encoded.len(), events.offset());
+
+        // Iteration stops at the first malformed item
+        encoded[19] = 0xFF;
+        let err = Events::new(encoded).find_map(Result::err).unwrap();
+        assert_eq!(ErrorKind::UnsupportedType, err.kind());
+        assert_eq!(Some(16), err.offset());
+        assert_eq!(&[0x420078, 0x420077], err.path());
+
+        let nested = nested(MAX_DEPTH + 1);
+        let err = Events::new(&nested).find_map(Result::err).unwrap();
+        assert_eq!(ErrorKind::NestingTooDeep, err.kind());
+        assert_eq!(Some(MAX_DEPTH * 8), err.offset());
+        Ok(())
+    }
+}
+
+#[cfg(all(test, not(feature = "alloc")))]
+mod no_alloc_tests {
+    use super::{Value::*, *};
+
+    #[test]
+    fn encode_view() -> Result<(), Error> {
+        let header = [Ttlv::new(0x420069u32, Integer(6))];
+        let children = [
+            Ttlv::new(0x420077u32, Structure(Items::Borrowed(&header))),
+            Ttlv::new(0x420079u32, TextString("message body".into())),
+        ];
+        let message = Ttlv::new(0x420078u32, Structure(Items::Borrowed(&children)));
+
+        let encoded = &mut [0u8; 64];
+        let encoded_len = message.encode(encoded)?;
+        assert_eq!(message.encoded_len(), encoded_len);
+
+        let (view, view_len) = TtlvRef::decode(encoded)?;
+        assert_eq!(encoded_len, view_len);
+        let version: i32 = view.path(&[0x420077u32, 0x420069])?.value()?;
+        assert_eq!(6, version);
+        let body = view.path(&[0x420079u32])?;
+        assert_eq!("message body", body.value::<&str>()?);
+        Ok(())
+    }
 }
@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use alloc::vec::Vec;
+use std::io::{self, Read};
+
+use crate::{util::message_len, Ttlv, DEFAULT_MAX_MESSAGE_LEN};
+
+/// Reads complete top-level TTLV messages from a byte stream, one at a time, into a buffer that is reused between
+/// messages.
+pub struct TtlvReader<R> {
+    reader: R,
+    buf: Vec<u8>,
+    max_len: usize,
+}
+
+impl<R: Read> TtlvReader<R> {
+    pub fn new(reader: R) -> Self {
+        TtlvReader {
+            reader,
+            buf: Vec::new(),
+            max_len: DEFAULT_MAX_MESSAGE_LEN,
+        }
+    }
+    /// Rejects messages whose encoded length, including the header, exceeds `max_len` before reading their body.
+    /// Defaults to `DEFAULT_MAX_MESSAGE_LEN`.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Reads and strictly decodes the next message. Returns `None` if the stream ends cleanly between messages.
+    pub fn read(&mut self) -> io::Result<Option<Ttlv<'_>>> {
+        let mut header = [0u8; 8];
+        let mut header_len = 0;
+        while header_len < header.len() {
+            match self.reader.read(&mut header[header_len..]) {
+                Ok(0) if header_len == 0 => return Ok(None),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(n) => header_len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let len = message_len(&header, self.max_len)?;
+        self.buf.clear();
+        self.buf.extend_from_slice(&header);
+        self.buf.resize(len, 0);
+        self.reader.read_exact(&mut self.buf[8..])?;
+        let (ttlv, _) = Ttlv::decode_strict(&self.buf)?;
+        Ok(Some(ttlv))
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
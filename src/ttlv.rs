@@ -1,27 +1,30 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use alloc::vec::Vec;
-use core::{slice::Iter, str::from_utf8};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::ToOwned, string::String, vec, vec::Vec};
+use core::{fmt, ops::Deref, slice::Iter, str::from_utf8};
 
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::FromPrimitive;
 use scroll::{Cread, Cwrite, BE};
 
-use crate::util::*;
+use crate::{util::*, Error, ErrorKind};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Ttlv<'a> {
-    tag: u16,
-    value: Value<'a>,
+    pub(crate) tag: u32, // 24 bits on the wire
+    pub(crate) value: Value<'a>,
 }
 
+/// A TTLV tree that owns all of its strings and bytes.
+#[cfg(feature = "alloc")]
+pub type OwnedTtlv = Ttlv<'static>;
+
 pub trait Tag: Sized + PartialEq {
-    fn from_u16(n: u16) -> Self;
-    fn to_u16(&self) -> u16;
+    fn from_u32(n: u32) -> Self;
+    fn to_u32(&self) -> u32;
 }
 
-#[derive(Debug, Clone, FromPrimitive, ToPrimitive)]
-enum Type {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
     Structure = 0x01,
     Integer,
     LongInteger,
@@ -32,62 +35,230 @@ enum Type {
     ByteString,
     DateTime,
     Interval,
+    DateTimeExtended,
+}
+
+/// Defines contents that borrow from the caller or, with the `alloc` feature, are owned, like `Cow`. The types are the
+/// same under every feature set; `alloc` only adds the `Owned` variant.
+macro_rules! contents {
+    ($(#[$meta:meta])* $name:ident<$a:lifetime>($borrowed:ty, $owned:ty)) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        #[non_exhaustive]
+        pub enum $name<$a> {
+            Borrowed(&$a $borrowed),
+            #[cfg(feature = "alloc")]
+            Owned($owned),
+        }
+
+        impl<$a> $name<$a> {
+            /// Extracts the owned contents, cloning them if borrowed.
+            #[cfg(feature = "alloc")]
+            pub fn into_owned(self) -> $owned {
+                match self {
+                    $name::Borrowed(val) => val.to_owned(),
+                    $name::Owned(val) => val,
+                }
+            }
+        }
+
+        impl<$a> Deref for $name<$a> {
+            type Target = $borrowed;
+            fn deref(&self) -> &Self::Target {
+                match self {
+                    $name::Borrowed(val) => val,
+                    #[cfg(feature = "alloc")]
+                    $name::Owned(val) => val,
+                }
+            }
+        }
+        impl<$a> AsRef<$borrowed> for $name<$a> {
+            fn as_ref(&self) -> &$borrowed {
+                self
+            }
+        }
+        impl<$a> fmt::Debug for $name<$a> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt::Debug::fmt(&**self, f)
+            }
+        }
+        impl<$a> PartialEq for $name<$a> {
+            fn eq(&self, other: &Self) -> bool {
+                **self == **other
+            }
+        }
+
+        impl<$a> From<&$a $borrowed> for $name<$a> {
+            fn from(val: &$a $borrowed) -> Self {
+                $name::Borrowed(val)
+            }
+        }
+        #[cfg(feature = "alloc")]
+        impl<$a> From<$owned> for $name<$a> {
+            fn from(val: $owned) -> Self {
+                $name::Owned(val)
+            }
+        }
+    };
+}
+
+contents! {
+    /// Children of a structure.
+    Items<'a>([Ttlv<'a>], Vec<Ttlv<'a>>)
+}
+contents! {
+    /// Byte string and Big Integer contents.
+    Bytes<'a>([u8], Vec<u8>)
+}
+contents! {
+    /// Text string contents.
+    Text<'a>(str, String)
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value<'a> {
-    Structure(Vec<Ttlv<'a>>),
+    Structure(Items<'a>),
     Integer(i32),
     LongInteger(i64),
-    BigInteger(&'a [u8]), // Not fully supported
+    BigInteger(Bytes<'a>), // Big-endian two's complement, sign-extended to a multiple of 8 bytes on encode
     Enumeration(u32),
     Boolean(bool),
-    TextString(&'a str),
-    ByteString(&'a [u8]),
+    TextString(Text<'a>),
+    ByteString(Bytes<'a>),
     DateTime(i64), // POSIX Time, as described in IEEE Standard 1003.1 [FIPS202]
     Interval(u32),
+    DateTimeExtended(i64), // Microseconds since the Unix epoch (KMIP 2.0)
+}
+
+pub(crate) const MAX_TAG: u32 = 0xFF_FFFF;
+
+/// Deepest structure nesting that decoding, `Events` and the text encodings will follow.
+pub const MAX_DEPTH: usize = 32;
+
+impl Type {
+    pub(crate) fn from_u8(n: u8) -> Option<Self> {
+        Some(match n {
+            0x01 => Type::Structure,
+            0x02 => Type::Integer,
+            0x03 => Type::LongInteger,
+            0x04 => Type::BigInteger,
+            0x05 => Type::Enumeration,
+            0x06 => Type::Boolean,
+            0x07 => Type::TextString,
+            0x08 => Type::ByteString,
+            0x09 => Type::DateTime,
+            0x0A => Type::Interval,
+            0x0B => Type::DateTimeExtended,
+            _ => return None,
+        })
+    }
+
+    /// The name used for this type in the KMIP JSON and XML encodings, e.g. `TextString`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Type::Structure => "Structure",
+            Type::Integer => "Integer",
+            Type::LongInteger => "LongInteger",
+            Type::BigInteger => "BigInteger",
+            Type::Enumeration => "Enumeration",
+            Type::Boolean => "Boolean",
+            Type::TextString => "TextString",
+            Type::ByteString => "ByteString",
+            Type::DateTime => "DateTime",
+            Type::Interval => "Interval",
+            Type::DateTimeExtended => "DateTimeExtended",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0x01..=0x0B)
+            .filter_map(Type::from_u8)
+            .find(|type_| type_.name() == name)
+    }
+
+    /// Whether the spec allows a value of this type to have the given (unpadded) length.
+    fn is_valid_len(&self, len: usize) -> bool {
+        match self {
+            Type::Integer | Type::Enumeration | Type::Interval => len == 4,
+            Type::LongInteger | Type::Boolean | Type::DateTime | Type::DateTimeExtended => len == 8,
+            Type::BigInteger => len.is_multiple_of(8),
+            Type::Structure | Type::TextString | Type::ByteString => true,
+        }
+    }
 }
 
-const START_BYTE: u8 = 0x42;
-
-/// The common error type returned for all TTLV-related failures. Variants can be used for more targetted error-handling.
-#[derive(Debug)]
-pub enum Error {
-    UnsupportedType,
-    TypeMismatch,
-    ChildNotFound,
-    MissingStartByte,
-    InsufficientBufferSize,
-    CorruptUtf8,
+impl<'a> Value<'a> {
+    /// Copies any borrowed strings and bytes so the value no longer borrows from the decode buffer.
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Structure(children) => Value::Structure(Items::Owned(
+                children
+                    .into_owned()
+                    .into_iter()
+                    .map(Ttlv::into_owned)
+                    .collect(),
+            )),
+            Value::Integer(val) => Value::Integer(val),
+            Value::LongInteger(val) => Value::LongInteger(val),
+            Value::BigInteger(val) => Value::BigInteger(Bytes::Owned(val.into_owned())),
+            Value::Enumeration(val) => Value::Enumeration(val),
+            Value::Boolean(val) => Value::Boolean(val),
+            Value::TextString(val) => Value::TextString(Text::Owned(val.into_owned())),
+            Value::ByteString(val) => Value::ByteString(Bytes::Owned(val.into_owned())),
+            Value::DateTime(val) => Value::DateTime(val),
+            Value::Interval(val) => Value::Interval(val),
+            Value::DateTimeExtended(val) => Value::DateTimeExtended(val),
+        }
+    }
+    pub(crate) fn type_(&self) -> Type {
+        match self {
+            Value::Structure(_) => Type::Structure,
+            Value::Integer(_) => Type::Integer,
+            Value::LongInteger(_) => Type::LongInteger,
+            Value::BigInteger(_) => Type::BigInteger,
+            Value::Enumeration(_) => Type::Enumeration,
+            Value::Boolean(_) => Type::Boolean,
+            Value::TextString(_) => Type::TextString,
+            Value::ByteString(_) => Type::ByteString,
+            Value::DateTime(_) => Type::DateTime,
+            Value::Interval(_) => Type::Interval,
+            Value::DateTimeExtended(_) => Type::DateTimeExtended,
+        }
+    }
 }
 
 impl<'a> Ttlv<'a> {
     pub fn new<T: Tag>(tag: T, value: Value<'a>) -> Self {
         Ttlv {
-            tag: tag.to_u16(),
+            tag: tag.to_u32(),
             value,
         }
     }
+    /// Converts into an owned tree that can outlive the buffer it was decoded from.
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> OwnedTtlv {
+        Ttlv {
+            tag: self.tag,
+            value: self.value.into_owned(),
+        }
+    }
     pub fn tag<T: Tag>(&self) -> T {
-        T::from_u16(self.tag)
+        T::from_u32(self.tag)
     }
     pub fn value<T: TryFromValue<'a>>(&'a self) -> Result<T, Error> {
-        T::try_from(&self.value).ok_or(Error::TypeMismatch)
+        T::try_from(&self.value).ok_or_else(|| self.type_mismatch(T::TYPE))
     }
-    pub fn child_iter(&self) -> Result<Iter<Ttlv>, Error> {
+    pub fn child_iter(&self) -> Result<Iter<'_, Ttlv<'a>>, Error> {
         if let Value::Structure(val) = &self.value {
             Ok(val.iter())
         } else {
-            Err(Error::TypeMismatch)
+            Err(self.type_mismatch(Type::Structure))
         }
     }
-    pub fn path<T: Tag>(&self, tags: &[T]) -> Result<&Ttlv, Error> {
+    pub fn path<T: Tag>(&self, tags: &[T]) -> Result<&Ttlv<'a>, Error> {
         self.child_iter()?
-            .find(|c| {
-                let child_tag: T = c.tag();
-                child_tag == tags[0]
-            })
-            .ok_or(Error::ChildNotFound)
+            .find(|c| c.tag == tags[0].to_u32())
+            .ok_or_else(|| Error::new(ErrorKind::ChildNotFound).with_tag(tags[0].to_u32()))
             .and_then(|c| {
                 if tags.len() == 1 {
                     Ok(c)
@@ -95,18 +266,43 @@ impl<'a> Ttlv<'a> {
                     c.path(&tags[1..])
                 }
             })
+            .map_err(|e| e.within(self.tag, 0))
+    }
+    fn type_mismatch(&self, expected: Type) -> Error {
+        Error::new(ErrorKind::TypeMismatch)
+            .with_tag(self.tag)
+            .with_types(expected, self.value.type_())
+    }
+
+    /// Returns exactly how many bytes `encode` will write for this item, including padding and nested structures.
+    pub fn encoded_len(&self) -> usize {
+        8 + match &self.value {
+            Value::Structure(children) => children.iter().map(Ttlv::encoded_len).sum(),
+            Value::BigInteger(val) => big_integer_len(val.len()),
+            Value::ByteString(val) => padded_len(val.len()),
+            Value::TextString(val) => padded_len(val.len()),
+            _ => 8,
+        }
     }
 
     pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
-        if buf.len() < 16 {
-            return Err(Error::InsufficientBufferSize);
+        let min_len = match &self.value {
+            Value::Structure(_)
+            | Value::BigInteger(_)
+            | Value::TextString(_)
+            | Value::ByteString(_) => 8,
+            _ => 16,
+        };
+        if buf.len() < min_len {
+            return Err(ErrorKind::InsufficientBufferSize.into());
+        }
+        if self.tag > MAX_TAG {
+            return Err(Error::new(ErrorKind::InvalidTag).with_tag(self.tag));
         }
-        buf.cwrite_with::<u8>(START_BYTE, 0, BE);
-        buf.cwrite_with::<u16>(self.tag, 1, BE);
         let (type_, len) = match &self.value {
             Value::Structure(children) => {
                 let mut cursor = 8;
-                for c in children {
+                for c in children.iter() {
                     cursor += c.encode(&mut buf[cursor..])?;
                 }
                 (Type::Structure, cursor - 8)
@@ -121,7 +317,10 @@ impl<'a> Ttlv<'a> {
                 (Type::LongInteger, 8)
             }
             // Big Integers are padded with leading sign-extended bytes (which are included in the length).
-            Value::BigInteger(_) => return Err(Error::UnsupportedType),
+            Value::BigInteger(val) => {
+                buf.write_sign_extended(val, 8)?;
+                (Type::BigInteger, big_integer_len(val.len()))
+            }
             Value::Enumeration(val) => {
                 buf.cwrite_with::<u32>(*val, 8, BE);
                 buf.cwrite_with::<u32>(0, 12, BE);
@@ -132,7 +331,7 @@ impl<'a> Ttlv<'a> {
                 (Type::Boolean, 8)
             }
             Value::TextString(val) => {
-                buf.write_var(val, 8)?;
+                buf.write_var(val.as_bytes(), 8)?;
                 (Type::TextString, val.len())
             }
             Value::ByteString(val) => {
@@ -148,48 +347,170 @@ impl<'a> Ttlv<'a> {
                 buf.cwrite_with::<u32>(0, 12, BE);
                 (Type::Interval, 4)
             }
+            Value::DateTimeExtended(val) => {
+                buf.cwrite_with::<i64>(*val, 8, BE);
+                (Type::DateTimeExtended, 8)
+            }
         };
-        buf.cwrite_with::<u8>(type_ as u8, 3, BE);
-        buf.cwrite_with::<u32>(len as u32, 4, BE);
+        self.encode_header(buf, type_, len);
         Ok(8 + padded_len(len))
     }
 
-    pub fn decode(buf: &'a [u8]) -> Result<(Self, usize), Error> {
-        if buf.len() < 8 {
-            return Err(Error::InsufficientBufferSize);
-        }
-        if buf.cread_with::<u8>(0, BE) != START_BYTE {
-            return Err(Error::MissingStartByte);
-        }
+    /// Encodes into a newly allocated buffer of exactly `encoded_len` bytes.
+    #[cfg(feature = "alloc")]
+    pub fn encode_to_vec(&self) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![0; self.encoded_len()];
+        self.encode(&mut buf)?;
+        Ok(buf)
+    }
 
-        let tag = buf.cread_with::<u16>(1, BE);
-        let type_ = Type::from_u8(buf.cread_with::<u8>(3, BE)).ok_or(Error::UnsupportedType)?;
-        let len = buf.cread_with::<u32>(4, BE) as usize;
-        let padded_len = padded_len(len);
-        if buf.len() < 8 + padded_len {
-            return Err(Error::InsufficientBufferSize);
+    /// Encodes into `writer`, streaming structures child by child. Returns the number of bytes written.
+    #[cfg(feature = "std")]
+    pub fn encode_to_writer<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<usize> {
+        if let Value::Structure(children) = &self.value {
+            if self.tag > MAX_TAG {
+                return Err(Error::new(ErrorKind::InvalidTag).with_tag(self.tag).into());
+            }
+            let len = self.encoded_len();
+            let mut header = [0u8; 8];
+            self.encode_header(&mut header, Type::Structure, len - 8);
+            writer.write_all(&header)?;
+            for c in children.iter() {
+                c.encode_to_writer(writer)?;
+            }
+            Ok(len)
+        } else {
+            let buf = self.encode_to_vec()?;
+            writer.write_all(&buf)?;
+            Ok(buf.len())
         }
+    }
 
+    fn encode_header(&self, buf: &mut [u8], type_: Type, len: usize) {
+        buf.cwrite_with::<u32>(self.tag << 8 | type_ as u32, 0, BE);
+        buf.cwrite_with::<u32>(len as u32, 4, BE);
+    }
+
+    /// Decodes a TTLV item, skipping over any structure children that fail to decode, including structures nested
+    /// deeper than `MAX_DEPTH`. Tolerates non-conformant values, such as unexpected lengths, non-zero padding and
+    /// Booleans other than 0 and 1, as long as they can be read.
+    #[cfg(feature = "alloc")]
+    pub fn decode(buf: &'a [u8]) -> Result<(Self, usize), Error> {
+        Self::decode_with(buf, false, 0)
+    }
+
+    /// Decodes a TTLV item, failing with the inner error if any structure child fails to decode or if the child
+    /// lengths don't add up exactly to the structure length. Also rejects lengths that don't suit the type, non-zero
+    /// padding, Booleans other than 0 and 1, and structures nested deeper than `MAX_DEPTH`.
+    #[cfg(feature = "alloc")]
+    pub fn decode_strict(buf: &'a [u8]) -> Result<(Self, usize), Error> {
+        Self::decode_with(buf, true, 0)
+    }
+
+    /// Decodes an item nested in `depth` structures.
+    #[cfg(feature = "alloc")]
+    fn decode_with(buf: &'a [u8], strict: bool, depth: usize) -> Result<(Self, usize), Error> {
+        let (tag, type_, len) = decode_header(buf, strict)?;
         let value = match type_ {
+            Type::Structure if depth == MAX_DEPTH => {
+                return Err(Error::new(ErrorKind::NestingTooDeep).at(0).with_tag(tag));
+            }
+            Type::Structure if strict => {
+                let mut cursor = 8;
+                let mut children = Vec::new();
+                while cursor < 8 + len {
+                    let (c, c_len) = Ttlv::decode_with(&buf[cursor..8 + len], true, depth + 1)
+                        .map_err(|e| e.within(tag, cursor))?;
+                    cursor += c_len;
+                    children.push(c);
+                }
+                Value::Structure(children.into())
+            }
             Type::Structure => {
                 let mut cursor = 8;
                 let mut children = Vec::new();
-                while let Ok((c, c_len)) = Ttlv::decode(&buf[cursor..8 + len]) {
+                while let Ok((c, c_len)) =
+                    Ttlv::decode_with(&buf[cursor..8 + len], false, depth + 1)
+                {
                     cursor += c_len;
                     children.push(c);
                 }
-                Value::Structure(children)
-            }
-            Type::Integer => Value::Integer(buf.cread_with::<i32>(8, BE)),
-            Type::LongInteger => Value::LongInteger(buf.cread_with::<i64>(8, BE)),
-            Type::BigInteger => Value::BigInteger(&buf[8..8 + len]),
-            Type::Enumeration => Value::Enumeration(buf.cread_with::<u32>(8, BE)),
-            Type::Boolean => Value::Boolean(buf.cread_with::<u64>(8, BE) != 0),
-            Type::TextString => Value::TextString(from_utf8(&buf[8..8 + len])?),
-            Type::ByteString => Value::ByteString(&buf[8..8 + len]),
-            Type::DateTime => Value::DateTime(buf.cread_with::<i64>(8, BE)),
-            Type::Interval => Value::Interval(buf.cread_with::<u32>(8, BE)),
+                Value::Structure(children.into())
+            }
+            _ => decode_primitive(buf, tag, type_, len, strict)?,
         };
-        Ok((Ttlv::new(tag, value), 8 + padded_len))
+        Ok((Ttlv::new(tag, value), 8 + padded_len(len)))
+    }
+}
+
+/// Reads the header of the item at the start of `buf`, returning its tag, type and unpadded length, and checks that the
+/// whole item fits in `buf`. With `strict`, also checks that the length suits the type and, for primitives, that the
+/// padding is zero; otherwise fixed-size values only need a non-zero length so that they can be read, and Big Integers
+/// a multiple of 8 bytes.
+pub(crate) fn decode_header(buf: &[u8], strict: bool) -> Result<(u32, Type, usize), Error> {
+    if buf.len() < 8 {
+        return Err(Error::new(ErrorKind::InsufficientBufferSize).at(0));
     }
+    let tag = buf.cread_with::<u32>(0, BE) >> 8;
+    let err = |kind| Error::new(kind).at(0).with_tag(tag);
+    let type_ = Type::from_u8(buf.cread_with::<u8>(3, BE))
+        .ok_or_else(|| err(ErrorKind::UnsupportedType))?;
+    let len = buf.cread_with::<u32>(4, BE) as usize;
+    let padded_len = padded_len(len);
+    if buf.len() < 8 + padded_len {
+        return Err(err(ErrorKind::InsufficientBufferSize));
+    }
+    let readable = match type_ {
+        Type::Structure | Type::TextString | Type::ByteString => true,
+        Type::BigInteger => len.is_multiple_of(8),
+        _ => len > 0,
+    };
+    if !(if strict {
+        type_.is_valid_len(len)
+    } else {
+        readable
+    }) {
+        return Err(err(ErrorKind::InvalidLength));
+    }
+    if strict
+        && !matches!(type_, Type::Structure)
+        && buf[8 + len..8 + padded_len].iter().any(|b| *b != 0)
+    {
+        return Err(err(ErrorKind::NonZeroPadding));
+    }
+    Ok((tag, type_, len))
+}
+
+/// Decodes the value of a primitive item whose header has been checked by `decode_header`. With `strict`, Booleans other
+/// than 0 and 1 are rejected; otherwise any non-zero value is true.
+pub(crate) fn decode_primitive(
+    buf: &[u8],
+    tag: u32,
+    type_: Type,
+    len: usize,
+    strict: bool,
+) -> Result<Value<'_>, Error> {
+    let err = |kind| Error::new(kind).at(0).with_tag(tag);
+    Ok(match type_ {
+        Type::Structure => unreachable!("structures are not primitives"),
+        Type::Integer => Value::Integer(buf.cread_with::<i32>(8, BE)),
+        Type::LongInteger => Value::LongInteger(buf.cread_with::<i64>(8, BE)),
+        Type::BigInteger => Value::BigInteger((&buf[8..8 + len]).into()),
+        Type::Enumeration => Value::Enumeration(buf.cread_with::<u32>(8, BE)),
+        Type::Boolean => match buf.cread_with::<u64>(8, BE) {
+            0 => Value::Boolean(false),
+            1 => Value::Boolean(true),
+            _ if strict => return Err(err(ErrorKind::InvalidBoolean)),
+            _ => Value::Boolean(true),
+        },
+        Type::TextString => Value::TextString(
+            from_utf8(&buf[8..8 + len])
+                .map_err(|_| err(ErrorKind::CorruptUtf8))?
+                .into(),
+        ),
+        Type::ByteString => Value::ByteString((&buf[8..8 + len]).into()),
+        Type::DateTime => Value::DateTime(buf.cread_with::<i64>(8, BE)),
+        Type::Interval => Value::Interval(buf.cread_with::<u32>(8, BE)),
+        Type::DateTimeExtended => Value::DateTimeExtended(buf.cread_with::<i64>(8, BE)),
+    })
 }
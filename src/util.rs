@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use core::{convert::AsRef, str::Utf8Error};
+use core::{convert::AsRef, fmt};
 
 use num_traits::{FromPrimitive, ToPrimitive};
 use scroll::{Cread, BE};
@@ -9,6 +9,7 @@ use crate::*;
 
 pub trait WriteVar {
     fn write_var<T: AsRef<[u8]>>(&mut self, data: T, offset: usize) -> Result<(), Error>;
+    fn write_sign_extended(&mut self, data: &[u8], offset: usize) -> Result<(), Error>;
 }
 
 impl WriteVar for [u8] {
@@ -19,7 +20,7 @@ impl WriteVar for [u8] {
         let padded_len = padded_len(data_len);
 
         if buf.len() < padded_len {
-            return Err(Error::InsufficientBufferSize);
+            return Err(ErrorKind::InsufficientBufferSize.into());
         }
         buf[..data_len].copy_from_slice(data.as_ref());
         for pad in &mut buf[data_len..padded_len] {
@@ -27,6 +28,23 @@ impl WriteVar for [u8] {
         }
         Ok(())
     }
+
+    fn write_sign_extended(&mut self, data: &[u8], offset: usize) -> Result<(), Error> {
+        let buf = &mut self[offset..];
+
+        let padded_len = big_integer_len(data.len());
+        let sign_len = padded_len - data.len();
+
+        if buf.len() < padded_len {
+            return Err(ErrorKind::InsufficientBufferSize.into());
+        }
+        let sign = if is_negative(data) { 0xFF } else { 0x00 };
+        for pad in &mut buf[..sign_len] {
+            *pad = sign;
+        }
+        buf[sign_len..padded_len].copy_from_slice(data);
+        Ok(())
+    }
 }
 
 pub fn parse_ttlv_len(buf: &[u8]) -> usize {
@@ -36,28 +54,67 @@ pub fn parse_ttlv_len(buf: &[u8]) -> usize {
 }
 
 pub fn padded_len(len: usize) -> usize {
-    (len + 7) / 8 * 8
+    len.div_ceil(8) * 8
 }
 
-impl<T: FromPrimitive + ToPrimitive + PartialEq> Tag for T {
-    fn from_u16(n: u16) -> Self {
-        FromPrimitive::from_u16(n).expect("Could not convert from u16")
+/// Default limit on the encoded length of a message, including its header, for `TtlvReader` and `TtlvDecoder`.
+pub const DEFAULT_MAX_MESSAGE_LEN: usize = 1024 * 1024;
+
+/// Encoded length of the top-level message starting with the 8-byte `header`, including the header. Rejects unsupported
+/// types and messages longer than `max_len`, so that callers can stop before buffering their body.
+#[cfg(feature = "alloc")]
+pub(crate) fn message_len(header: &[u8], max_len: usize) -> Result<usize, Error> {
+    let tag = header.cread_with::<u32>(0, BE) >> 8;
+    let err = |kind| Error::new(kind).at(0).with_tag(tag);
+    Type::from_u8(header[3]).ok_or_else(|| err(ErrorKind::UnsupportedType))?;
+    let len = 8 + padded_len(header.cread_with::<u32>(4, BE) as usize);
+    if len > max_len {
+        return Err(err(ErrorKind::MessageTooLarge));
     }
-    fn to_u16(&self) -> u16 {
-        ToPrimitive::to_u16(self).expect("Could not convert to u16")
+    Ok(len)
+}
+
+/// Encoded length of a Big Integer with `len` significant bytes: sign-extended to a multiple of 8 bytes, and never empty
+/// so that zero is written as eight zero bytes.
+pub(crate) fn big_integer_len(len: usize) -> usize {
+    padded_len(len).max(8)
+}
+
+pub(crate) fn is_negative(twos_complement: &[u8]) -> bool {
+    twos_complement.first().is_some_and(|b| b & 0x80 != 0)
+}
+
+/// Formats bytes as contiguous uppercase hex digits.
+pub struct Hex<'a>(pub &'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
     }
 }
 
-impl From<Utf8Error> for Error {
-    fn from(_: Utf8Error) -> Self {
-        Error::CorruptUtf8
+impl<T: FromPrimitive + ToPrimitive + PartialEq> Tag for T {
+    fn from_u32(n: u32) -> Self {
+        FromPrimitive::from_u32(n).expect("Could not convert from u32")
+    }
+    fn to_u32(&self) -> u32 {
+        ToPrimitive::to_u32(self).expect("Could not convert to u32")
     }
 }
 
 pub trait TryFromValue<'a>: Sized {
+    /// The type of value this conversion accepts, reported on mismatch.
+    const TYPE: Type;
     fn try_from(value: &'a Value) -> Option<Self>;
+    /// Converts a value whose contents are borrowed for `'a`, such as one decoded by `TtlvRef`, without borrowing the
+    /// value itself. Owned contents don't live for `'a` and are rejected.
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self>;
 }
 impl<'a> TryFromValue<'a> for i32 {
+    const TYPE: Type = Type::Integer;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::Integer(val) = value {
             Some(*val)
@@ -65,8 +122,12 @@ impl<'a> TryFromValue<'a> for i32 {
             None
         }
     }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
 }
 impl<'a> TryFromValue<'a> for i64 {
+    const TYPE: Type = Type::LongInteger;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::LongInteger(val) = value {
             Some(*val)
@@ -74,8 +135,12 @@ impl<'a> TryFromValue<'a> for i64 {
             None
         }
     }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
 }
 impl<'a> TryFromValue<'a> for u32 {
+    const TYPE: Type = Type::Enumeration;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::Enumeration(val) = value {
             Some(*val)
@@ -83,8 +148,12 @@ impl<'a> TryFromValue<'a> for u32 {
             None
         }
     }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
 }
 impl<'a> TryFromValue<'a> for bool {
+    const TYPE: Type = Type::Boolean;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::Boolean(val) = value {
             Some(*val)
@@ -92,10 +161,21 @@ impl<'a> TryFromValue<'a> for bool {
             None
         }
     }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
 }
 impl<'a> TryFromValue<'a> for &'a str {
+    const TYPE: Type = Type::TextString;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::TextString(val) = value {
+            Some(val.as_ref())
+        } else {
+            None
+        }
+    }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        if let Value::TextString(Text::Borrowed(val)) = value {
             Some(val)
         } else {
             None
@@ -103,11 +183,98 @@ impl<'a> TryFromValue<'a> for &'a str {
     }
 }
 impl<'a> TryFromValue<'a> for &'a [u8] {
+    const TYPE: Type = Type::ByteString;
     fn try_from(value: &'a Value) -> Option<Self> {
         if let Value::ByteString(val) = value {
+            Some(val.as_ref())
+        } else {
+            None
+        }
+    }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        if let Value::ByteString(Bytes::Borrowed(val)) = value {
             Some(val)
         } else {
             None
         }
     }
 }
+
+/// Big-endian two's-complement bytes of a Big Integer, with redundant leading sign-extension bytes stripped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TwosComplement<'a>(pub &'a [u8]);
+
+impl<'a> From<TwosComplement<'a>> for Value<'a> {
+    fn from(val: TwosComplement<'a>) -> Self {
+        Value::BigInteger(val.0.into())
+    }
+}
+impl<'a> TwosComplement<'a> {
+    fn from_sign_extended(val: &'a [u8]) -> Self {
+        let sign = if is_negative(val) { 0xFF } else { 0x00 };
+        let mut start = 0;
+        while start + 1 < val.len()
+            && val[start] == sign
+            && (val[start + 1] & 0x80 != 0) == (sign != 0)
+        {
+            start += 1;
+        }
+        TwosComplement(&val[start..])
+    }
+}
+impl<'a> TryFromValue<'a> for TwosComplement<'a> {
+    const TYPE: Type = Type::BigInteger;
+    fn try_from(value: &'a Value) -> Option<Self> {
+        if let Value::BigInteger(val) = value {
+            Some(TwosComplement::from_sign_extended(val))
+        } else {
+            None
+        }
+    }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        if let Value::BigInteger(Bytes::Borrowed(val)) = value {
+            Some(TwosComplement::from_sign_extended(val))
+        } else {
+            None
+        }
+    }
+}
+impl<'a> TryFromValue<'a> for i128 {
+    const TYPE: Type = Type::BigInteger;
+    fn try_from(value: &'a Value) -> Option<Self> {
+        let TwosComplement(val) = TwosComplement::try_from(value)?;
+        if val.len() > 16 {
+            return None;
+        }
+        let sign = if is_negative(val) { 0xFF } else { 0x00 };
+        let mut bytes = [sign; 16];
+        bytes[16 - val.len()..].copy_from_slice(val);
+        Some(i128::from_be_bytes(bytes))
+    }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
+}
+
+/// Microseconds since the Unix epoch, as carried by a Date Time Extended value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateTimeMicros(pub i64);
+
+impl<'a> From<DateTimeMicros> for Value<'a> {
+    fn from(val: DateTimeMicros) -> Self {
+        Value::DateTimeExtended(val.0)
+    }
+}
+impl<'a> TryFromValue<'a> for DateTimeMicros {
+    const TYPE: Type = Type::DateTimeExtended;
+    fn try_from(value: &'a Value) -> Option<Self> {
+        if let Value::DateTimeExtended(val) = value {
+            Some(DateTimeMicros(*val))
+        } else {
+            None
+        }
+    }
+    fn try_from_borrowed(value: &Value<'a>) -> Option<Self> {
+        TryFromValue::try_from(value)
+    }
+}
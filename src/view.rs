@@ -0,0 +1,122 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{
+    ttlv::{decode_header, decode_primitive},
+    util::{padded_len, TryFromValue},
+    Error, ErrorKind, Tag, Type, Value,
+};
+
+/// A borrowed view of an encoded TTLV item that parses structures lazily, in place, without allocating. Primitive
+/// values are validated when the view is created; structure children are validated as they are iterated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TtlvRef<'a> {
+    tag: u32,
+    contents: Contents<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Contents<'a> {
+    Structure(&'a [u8]), // Encoded children
+    Primitive(Value<'a>),
+}
+
+impl<'a> TtlvRef<'a> {
+    /// Creates a view of the item at the start of `buf`, returning it along with its padded encoded length.
+    pub fn decode(buf: &'a [u8]) -> Result<(Self, usize), Error> {
+        let (tag, type_, len) = decode_header(buf, true)?;
+        let contents = match type_ {
+            Type::Structure => Contents::Structure(&buf[8..8 + len]),
+            _ => Contents::Primitive(decode_primitive(buf, tag, type_, len, true)?),
+        };
+        Ok((TtlvRef { tag, contents }, 8 + padded_len(len)))
+    }
+
+    pub fn tag<T: Tag>(&self) -> T {
+        T::from_u32(self.tag)
+    }
+    /// Converts a primitive value. Strings and bytes borrow from the buffer the view was decoded from, so they can
+    /// outlive the view.
+    pub fn value<T: TryFromValue<'a>>(&self) -> Result<T, Error> {
+        let err = |actual| {
+            Error::new(ErrorKind::TypeMismatch)
+                .with_tag(self.tag)
+                .with_types(T::TYPE, actual)
+        };
+        match &self.contents {
+            Contents::Structure(_) => Err(err(Type::Structure)),
+            Contents::Primitive(value) => {
+                T::try_from_borrowed(value).ok_or_else(|| err(value.type_()))
+            }
+        }
+    }
+    /// Iterates over the children of a structure, decoding each as it is reached. Iteration ends after the first child
+    /// that fails to decode, including a partial child left over at the end of the structure.
+    pub fn children(&self) -> Result<Children<'a>, Error> {
+        match self.contents {
+            Contents::Structure(buf) => Ok(Children {
+                tag: self.tag,
+                buf,
+                cursor: 0,
+            }),
+            Contents::Primitive(ref value) => Err(Error::new(ErrorKind::TypeMismatch)
+                .with_tag(self.tag)
+                .with_types(Type::Structure, value.type_())),
+        }
+    }
+    pub fn path<T: Tag>(&self, tags: &[T]) -> Result<TtlvRef<'a>, Error> {
+        let tag = match tags.first() {
+            Some(tag) => tag.to_u32(),
+            None => return Err(Error::new(ErrorKind::ChildNotFound).within(self.tag, 0)),
+        };
+        let mut children = self.children()?;
+        let (child, offset) = loop {
+            let offset = 8 + children.cursor;
+            match children.next() {
+                Some(Ok(c)) if c.tag == tag => break (c, offset),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(e),
+                None => {
+                    return Err(Error::new(ErrorKind::ChildNotFound)
+                        .with_tag(tag)
+                        .within(self.tag, 0))
+                }
+            }
+        };
+        if tags.len() == 1 {
+            Ok(child)
+        } else {
+            child
+                .path(&tags[1..])
+                .map_err(|e| e.within(self.tag, offset))
+        }
+    }
+}
+
+/// Iterator over the children of a `TtlvRef` structure.
+#[derive(Debug, Clone)]
+pub struct Children<'a> {
+    tag: u32,
+    buf: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Result<TtlvRef<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cursor >= self.buf.len() {
+            return None;
+        }
+        match TtlvRef::decode(&self.buf[self.cursor..]) {
+            Ok((c, c_len)) => {
+                self.cursor += c_len;
+                Some(Ok(c))
+            }
+            Err(e) => {
+                let offset = 8 + self.cursor;
+                self.cursor = self.buf.len();
+                Some(Err(e.within(self.tag, offset)))
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Derive macros for `ttlv::ToTtlv` and `ttlv::FromTtlv`. Every field of the struct needs a `#[ttlv(tag = ...)]`
+//! attribute naming the tag of the child it maps to, either as an integer such as `0x420069` or as any `ttlv::Tag`
+//! value such as `kmip::Tag::ProtocolVersion`. `Option` fields may be missing and `Vec` fields collect every child with
+//! the tag.
+
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::ParseStream, parse_macro_input, parse_quote, Data, DeriveInput, Error, Expr, ExprLit,
+    Fields, GenericParam, Ident, Lifetime, LifetimeDef, Lit, Token,
+};
+
+#[proc_macro_derive(ToTtlv, attributes(ttlv))]
+pub fn derive_to_ttlv(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_ttlv(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(FromTtlv, attributes(ttlv))]
+pub fn derive_from_ttlv(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    from_ttlv(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn to_ttlv(mut input: DeriveInput) -> Result<TokenStream, Error> {
+    let fields = tagged_fields(&input)?;
+    let (idents, tags): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::ttlv::ToTtlv));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ttlv::ToTtlv for #name #ty_generics #where_clause {
+            fn to_ttlv(&self, tag: u32) -> ::ttlv::Ttlv<'_> {
+                let mut children = ::core::default::Default::default();
+                #( ::ttlv::ToChildren::to_children(&self.#idents, #tags, &mut children); )*
+                ::ttlv::Ttlv::new(tag, ::ttlv::Value::Structure(children.into()))
+            }
+        }
+    })
+}
+
+fn from_ttlv(input: DeriveInput) -> Result<TokenStream, Error> {
+    let fields = tagged_fields(&input)?;
+    let (idents, tags): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+
+    // The decoded tree must outlive any borrows the struct holds
+    let ttlv_lifetime = Lifetime::new("'__ttlv", Span::call_site());
+    let mut generics = input.generics.clone();
+    let mut ttlv_def = LifetimeDef::new(ttlv_lifetime.clone());
+    ttlv_def
+        .bounds
+        .extend(input.generics.lifetimes().map(|def| def.lifetime.clone()));
+    generics.params.insert(0, GenericParam::Lifetime(ttlv_def));
+    for param in generics.type_params_mut() {
+        param
+            .bounds
+            .push(parse_quote!(::ttlv::FromTtlv<#ttlv_lifetime>));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ttlv::FromTtlv<#ttlv_lifetime> for #name #ty_generics #where_clause {
+            fn from_ttlv(ttlv: &#ttlv_lifetime ::ttlv::Ttlv<#ttlv_lifetime>) -> ::core::result::Result<Self, ::ttlv::Error> {
+                // Reject non-structures even when there are no fields to look up
+                let _ = ttlv.child_iter()?;
+                ::core::result::Result::Ok(#name {
+                    #( #idents: ::ttlv::FromTtlv::from_children(ttlv, #tags)?, )*
+                })
+            }
+        }
+    })
+}
+
+/// Collects each named field along with the tag from its `#[ttlv(tag = ...)]` attribute, as a `u32` expression.
+fn tagged_fields(input: &DeriveInput) -> Result<Vec<(Ident, TokenStream)>, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "TTLV structures need named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "only structs can be mapped to TTLV structures",
+            ))
+        }
+    };
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident.clone().expect("named field");
+            let mut tag = None;
+            for attr in field.attrs.iter().filter(|attr| attr.path.is_ident("ttlv")) {
+                tag = Some(attr.parse_args_with(|input: ParseStream| {
+                    let name: Ident = input.parse()?;
+                    if name != "tag" {
+                        return Err(Error::new_spanned(name, "unknown ttlv attribute"));
+                    }
+                    input.parse::<Token![=]>()?;
+                    input.parse::<Expr>()
+                })?);
+            }
+            match tag {
+                Some(Expr::Lit(ExprLit {
+                    lit: Lit::Int(lit), ..
+                })) => match lit.base10_parse::<u32>()? {
+                    tag if tag <= 0xFF_FFFF => Ok((ident, quote!(#tag))),
+                    _ => Err(Error::new_spanned(lit, "tag does not fit in 24 bits")),
+                },
+                Some(Expr::Lit(expr)) => Err(Error::new_spanned(expr, "tag must be an integer")),
+                Some(expr) => Ok((ident, quote!(::ttlv::Tag::to_u32(&(#expr))))),
+                None => Err(Error::new_spanned(
+                    field,
+                    "missing #[ttlv(tag = ...)] attribute",
+                )),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use ttlv::{kmip, Error, FromTtlv, ToChildren, ToTtlv, Ttlv};
+
+#[derive(Debug, PartialEq, ToTtlv, FromTtlv)]
+struct ProtocolVersion {
+    #[ttlv(tag = 0x42006A)]
+    major: i32,
+    #[ttlv(tag = 0x42006B)]
+    minor: i32,
+}
+
+#[derive(Debug, PartialEq, ToTtlv, FromTtlv)]
+struct RequestHeader<'a> {
+    #[ttlv(tag = kmip::Tag::ProtocolVersion)]
+    protocol_version: ProtocolVersion,
+    #[ttlv(tag = 0x420099)]
+    username: Option<&'a str>,
+    #[ttlv(tag = 0x420094)]
+    unique_identifiers: Vec<String>,
+}
+
+#[test]
+fn round_trip() -> Result<(), Error> {
+    let header = RequestHeader {
+        protocol_version: ProtocolVersion { major: 2, minor: 1 },
+        username: None,
+        unique_identifiers: vec!["1".into(), "2".into()],
+    };
+    let encoded = header.to_ttlv(0x420077).encode_to_vec()?;
+
+    let (decoded, _) = Ttlv::decode(&encoded)?;
+    assert_eq!(3, decoded.child_iter()?.count());
+    let minor: i32 = decoded.path(&[0x420069, 0x42006B])?.value()?;
+    assert_eq!(1, minor);
+    assert_eq!(header, RequestHeader::from_ttlv(&decoded)?);
+
+    // Missing optional values have no encoding of their own, only as a field
+    let mut children = Vec::new();
+    None::<i32>.to_children(0x420099, &mut children);
+    assert!(children.is_empty());
+    Ok(())
+}
+
+#[test]
+fn missing_field() {
+    let decoded = Ttlv::new(
+        0x420069,
+        ttlv::Value::Structure(vec![Ttlv::new(0x42006A, ttlv::Value::Integer(2))].into()),
+    );
+    let err = ProtocolVersion::from_ttlv(&decoded).unwrap_err();
+    assert_eq!(ttlv::ErrorKind::ChildNotFound, err.kind());
+    assert_eq!(Some(0x42006B), err.tag());
+}